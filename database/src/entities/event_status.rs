@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "event_status")]
 pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub season: String,
     #[sea_orm(primary_key, auto_increment = false)]
     pub gameweek: i32,
     pub deadline_time: DateTimeWithTimeZone,
@@ -21,6 +23,21 @@ pub struct Model {
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::season::Entity",
+        from = "Column::Season",
+        to = "super::season::Column::Name",
+        on_update = "Cascade",
+        on_delete = "Restrict"
+    )]
+    Season,
+}
+
+impl Related<super::season::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Season.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -2,6 +2,7 @@
 
 use super::sea_orm_active_enums::ChipRule;
 use super::sea_orm_active_enums::MatchStatus;
+use super::sea_orm_active_enums::OffendedRule;
 use super::sea_orm_active_enums::TransferRule;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -16,6 +17,7 @@ pub struct Model {
     pub created_date: DateTimeWithTimeZone,
     pub status: MatchStatus,
     pub gameweek: i32,
+    pub end_gameweek: Option<i32>,
     pub bet_amount: i32,
     pub transfer_rule: TransferRule,
     pub chip_rule: ChipRule,
@@ -29,10 +31,37 @@ pub struct Model {
     pub opponent_point: i32,
     pub owner_point: i32,
     pub winner_id: Option<i32>,
+    pub owner_offended_rule: Option<OffendedRule>,
+    pub opponent_offended_rule: Option<OffendedRule>,
+    pub min_week_started: Option<i32>,
+    #[sea_orm(unique)]
+    pub invite_code: Option<String>,
+    pub challenged_id: Option<i32>,
+    pub tournament_id: Option<i32>,
+    pub tournament_round: Option<i32>,
+    pub is_rated: bool,
+    pub min_rating: Option<i32>,
+    pub max_rating: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::tournament::Entity",
+        from = "Column::TournamentId",
+        to = "super::tournament::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    Tournament,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::ChallengedId",
+        to = "super::user::Column::Id",
+        on_update = "Cascade",
+        on_delete = "SetNull"
+    )]
+    User4,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::OpponentId",
@@ -59,4 +88,10 @@ pub enum Relation {
     User1,
 }
 
+impl Related<super::tournament::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Tournament.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -2,8 +2,16 @@
 
 pub mod prelude;
 
+pub mod coupon;
+pub mod coupon_redemption;
 pub mod event_status;
 pub mod r#match;
+pub mod payment;
 pub mod sea_orm_active_enums;
+pub mod season;
+pub mod tournament;
+pub mod tournament_entrant;
 pub mod transaction;
 pub mod user;
+pub mod user_identity;
+pub mod user_rating;
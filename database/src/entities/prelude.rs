@@ -1,6 +1,14 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.6
 
+pub use super::coupon::Entity as Coupon;
+pub use super::coupon_redemption::Entity as CouponRedemption;
 pub use super::event_status::Entity as EventStatus;
+pub use super::payment::Entity as Payment;
 pub use super::r#match::Entity as Match;
+pub use super::season::Entity as Season;
+pub use super::tournament::Entity as Tournament;
+pub use super::tournament_entrant::Entity as TournamentEntrant;
 pub use super::transaction::Entity as Transaction;
 pub use super::user::Entity as User;
+pub use super::user_identity::Entity as UserIdentity;
+pub use super::user_rating::Entity as UserRating;
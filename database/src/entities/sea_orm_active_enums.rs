@@ -16,6 +16,8 @@ pub enum ChipRule {
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "match_status")]
 pub enum MatchStatus {
+    #[sea_orm(string_value = "Cancelled")]
+    Cancelled,
     #[sea_orm(string_value = "Finished")]
     Finished,
     #[sea_orm(string_value = "Live")]
@@ -24,6 +26,40 @@ pub enum MatchStatus {
     Next,
 }
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "offended_rule")]
+pub enum OffendedRule {
+    #[sea_orm(string_value = "MinWeekStart")]
+    MinWeekStart,
+    #[sea_orm(string_value = "MissingEntry")]
+    MissingEntry,
+    #[sea_orm(string_value = "Transferring")]
+    Transferring,
+    #[sea_orm(string_value = "UsingChip")]
+    UsingChip,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "payment_status")]
+pub enum PaymentStatus {
+    #[sea_orm(string_value = "Failed")]
+    Failed,
+    #[sea_orm(string_value = "Pending")]
+    Pending,
+    #[sea_orm(string_value = "Succeeded")]
+    Succeeded,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "tournament_status")]
+pub enum TournamentStatus {
+    #[sea_orm(string_value = "Cancelled")]
+    Cancelled,
+    #[sea_orm(string_value = "Finished")]
+    Finished,
+    #[sea_orm(string_value = "Registering")]
+    Registering,
+    #[sea_orm(string_value = "Running")]
+    Running,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "transaction_flag")]
 pub enum TransactionFlag {
     #[sea_orm(string_value = "Down")]
@@ -34,12 +70,24 @@ pub enum TransactionFlag {
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "transaction_type")]
 pub enum TransactionType {
+    #[sea_orm(string_value = "Adjustment")]
+    Adjustment,
     #[sea_orm(string_value = "CreateMatch")]
     CreateMatch,
     #[sea_orm(string_value = "Event")]
     Event,
+    #[sea_orm(string_value = "JoinMatch")]
+    JoinMatch,
     #[sea_orm(string_value = "Purchase")]
     Purchase,
+    #[sea_orm(string_value = "Refund")]
+    Refund,
+    #[sea_orm(string_value = "TournamentEntry")]
+    TournamentEntry,
+    #[sea_orm(string_value = "TournamentPrize")]
+    TournamentPrize,
+    #[sea_orm(string_value = "WinMatch")]
+    WinMatch,
 }
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "transfer_rule")]
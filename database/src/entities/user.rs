@@ -17,12 +17,49 @@ pub struct Model {
     pub name: Option<String>,
     pub player_first_name: Option<String>,
     pub player_last_name: Option<String>,
+    pub is_admin: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::coupon::Entity")]
+    Coupon,
+    #[sea_orm(has_many = "super::coupon_redemption::Entity")]
+    CouponRedemption,
+    #[sea_orm(has_many = "super::payment::Entity")]
+    Payment,
+    #[sea_orm(has_many = "super::tournament_entrant::Entity")]
+    TournamentEntrant,
     #[sea_orm(has_many = "super::transaction::Entity")]
     Transaction,
+    #[sea_orm(has_many = "super::user_identity::Entity")]
+    UserIdentity,
+    #[sea_orm(has_many = "super::user_rating::Entity")]
+    UserRating,
+}
+
+impl Related<super::coupon::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Coupon.def()
+    }
+}
+
+impl Related<super::coupon_redemption::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CouponRedemption.def()
+    }
+}
+
+impl Related<super::payment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Payment.def()
+    }
+}
+
+impl Related<super::tournament_entrant::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TournamentEntrant.def()
+    }
 }
 
 impl Related<super::transaction::Entity> for Entity {
@@ -31,4 +68,16 @@ impl Related<super::transaction::Entity> for Entity {
     }
 }
 
+impl Related<super::user_identity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserIdentity.def()
+    }
+}
+
+impl Related<super::user_rating::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserRating.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
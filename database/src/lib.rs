@@ -5,6 +5,7 @@ pub mod entities;
 pub mod links;
 pub mod models;
 
+#[allow(clippy::derivable_impls)]
 impl Default for MatchStatus {
     fn default() -> Self {
         MatchStatus::Next
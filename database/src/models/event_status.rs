@@ -0,0 +1,88 @@
+use crate::entities::event_status;
+
+/// Gameweeks whose matches can be settled, FPL has checked their points and they are over.
+///
+/// A gameweek is over once the next one is current, except the last of the season which
+/// stays current until FPL resets for the next season.
+pub fn settleable_events(events: &[event_status::Model]) -> Vec<&event_status::Model> {
+    let has_next = events.iter().any(|event| event.is_next);
+
+    events
+        .iter()
+        .filter(|event| event.finished && event.data_checked)
+        .filter(|event| event.is_previous || (event.is_current && !has_next))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(gameweek: i32, finished: bool) -> event_status::Model {
+        event_status::Model {
+            season: "2023/24".to_owned(),
+            gameweek,
+            deadline_time: Default::default(),
+            finished,
+            data_checked: finished,
+            average_entry_score: 0,
+            highest_scoring_entry: 0,
+            deadline_time_epoch: 0,
+            is_previous: false,
+            is_current: false,
+            is_next: false,
+            name: format!("Gameweek {}", gameweek),
+        }
+    }
+
+    fn gameweeks(events: &[&event_status::Model]) -> Vec<i32> {
+        events.iter().map(|event| event.gameweek).collect()
+    }
+
+    #[test]
+    fn previous_gameweek_is_settled_once_checked() {
+        let previous = event_status::Model {
+            is_previous: true,
+            ..event(5, true)
+        };
+        let current = event_status::Model {
+            is_current: true,
+            ..event(6, true)
+        };
+        let next = event_status::Model {
+            is_next: true,
+            ..event(7, false)
+        };
+
+        let events = [previous, current, next];
+
+        // the current gameweek waits for the next one even when its points are checked
+        assert_eq!(gameweeks(&settleable_events(&events)), vec![5]);
+    }
+
+    #[test]
+    fn last_gameweek_is_settled_while_still_current() {
+        let previous = event_status::Model {
+            is_previous: true,
+            ..event(37, true)
+        };
+        let last = event_status::Model {
+            is_current: true,
+            ..event(38, false)
+        };
+
+        let events = [previous.clone(), last.clone()];
+        assert_eq!(gameweeks(&settleable_events(&events)), vec![37]);
+
+        // once its points are checked the season can settle all of its matches and roll over
+        let events = [
+            previous,
+            event_status::Model {
+                finished: true,
+                data_checked: true,
+                ..last
+            },
+        ];
+        assert_eq!(gameweeks(&settleable_events(&events)), vec![37, 38]);
+    }
+}
@@ -0,0 +1,39 @@
+use sea_orm::FromQueryResult;
+use serde::{Deserialize, Serialize};
+
+/// Win rate is meaningless over a handful of matches, so it needs this many by default.
+pub const MIN_GAMES_FOR_WIN_RATE: i64 = 10;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardMetric {
+    Profit,
+    Record,
+    WinRate,
+    Volume,
+}
+
+#[derive(Serialize, FromQueryResult)]
+pub struct LeaderboardEntry {
+    pub user_id: i32,
+    pub fpl_id: Option<i32>,
+    pub name: Option<String>,
+    pub games: i64,
+    pub wins: i64,
+    pub draws: i64,
+    pub losses: i64,
+    pub win_rate: f64,
+    /// d_coin won minus d_coin lost on settled matches.
+    pub profit: i64,
+    /// d_coin staked on settled matches.
+    pub volume: i64,
+}
+
+pub struct FindLeaderboardParams {
+    pub metric: LeaderboardMetric,
+    pub season: Option<String>,
+    pub from_gameweek: Option<i32>,
+    pub to_gameweek: Option<i32>,
+    pub min_games: i64,
+    pub page: u64,
+    pub take: u64,
+}
@@ -1,6 +1,8 @@
-use crate::entities::sea_orm_active_enums::MatchStatus;
-use sea_orm::FromQueryResult;
+use super::DebitError;
+use crate::entities::sea_orm_active_enums::{MatchStatus, OffendedRule};
+use sea_orm::{DbErr, FromQueryResult};
 use serde::Serialize;
+use std::cmp::Ordering;
 
 #[derive(Serialize)]
 pub struct MatchWithOwnerOpponentAndWinner {
@@ -16,11 +18,23 @@ pub struct MatchWithOwnerOpponentAndWinner {
     winner: Option<PlayerOnMatch>,
 }
 #[derive(Serialize)]
-
 struct PartialMatch {
     id: i32,
     gameweek: i32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_gameweek: Option<i32>,
+
     status: MatchStatus,
+    owner_point: i32,
+    opponent_point: i32,
+    is_draw: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner_offended_rule: Option<OffendedRule>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    opponent_offended_rule: Option<OffendedRule>,
 }
 
 #[derive(Serialize)]
@@ -48,7 +62,13 @@ impl FromQueryResult for MatchWithOwnerOpponentAndWinner {
         let r#match = PartialMatch {
             id: res.try_get("", "id")?,
             gameweek: res.try_get("", "gameweek")?,
+            end_gameweek: res.try_get("", "end_gameweek")?,
             status: res.try_get("", "status")?,
+            owner_point: res.try_get("", "owner_point")?,
+            opponent_point: res.try_get("", "opponent_point")?,
+            is_draw: res.try_get("", "is_draw")?,
+            owner_offended_rule: res.try_get("", "owner_offended_rule")?,
+            opponent_offended_rule: res.try_get("", "opponent_offended_rule")?,
         };
 
         let owner = PlayerOnMatch {
@@ -70,11 +90,11 @@ impl FromQueryResult for MatchWithOwnerOpponentAndWinner {
             None => None,
         };
 
-        let winner = match res.try_get("opponent_", "id")? {
+        let winner = match res.try_get("winner_", "id")? {
             Some(id) => Some(PlayerOnMatch {
                 id,
-                first_name: res.try_get("winner_", "first_name")?,
-                last_name: res.try_get("winner_", "last_name")?,
+                first_name: res.try_get("winner_", "player_first_name")?,
+                last_name: res.try_get("winner_", "player_last_name")?,
                 name: res.try_get("winner_", "name")?,
                 fpl_id: res.try_get("winner_", "fpl_id")?,
             }),
@@ -97,6 +117,231 @@ pub struct FindMatchesParams {
     pub created_by: Option<i32>,
     pub joined_by_or_created: Option<i32>,
     pub exclude_created_by: Option<i32>,
+    pub exclude_private: bool,
+    pub exclude_challenges: bool,
+    pub challenged: Option<i32>,
+    pub rated_only: bool,
+    /// Only matches whose rating band lets a player with this rating join.
+    pub joinable_with_rating: Option<i32>,
+    pub min_owner_rating: Option<i32>,
+    pub max_owner_rating: Option<i32>,
     pub status: MatchStatus,
     pub season: Option<String>,
 }
+
+pub struct MatchSettlement {
+    pub owner_point: i32,
+    pub opponent_point: i32,
+    pub owner_offended_rule: Option<OffendedRule>,
+    pub opponent_offended_rule: Option<OffendedRule>,
+}
+
+impl MatchSettlement {
+    /// The rule broken by whoever lost by forfeit.
+    pub fn forfeited_rule(&self, owner_id: i32, winner_id: i32) -> Option<&OffendedRule> {
+        if winner_id == owner_id {
+            self.opponent_offended_rule.as_ref()
+        } else {
+            self.owner_offended_rule.as_ref()
+        }
+    }
+
+    /// An offender forfeits the match whatever the points are, the match is a draw if both offended.
+    pub fn winner_id(&self, owner_id: i32, opponent_id: i32) -> Option<i32> {
+        match (&self.owner_offended_rule, &self.opponent_offended_rule) {
+            (Some(_), None) => Some(opponent_id),
+            (None, Some(_)) => Some(owner_id),
+            (Some(_), Some(_)) => None,
+            (None, None) => match self.owner_point.cmp(&self.opponent_point) {
+                Ordering::Greater => Some(owner_id),
+                Ordering::Less => Some(opponent_id),
+                Ordering::Equal => None,
+            },
+        }
+    }
+}
+
+impl OffendedRule {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            OffendedRule::Transferring => "made more transfers than the match allows",
+            OffendedRule::UsingChip => "played a chip the match does not allow",
+            OffendedRule::MinWeekStart => "started their FPL team later than the match allows",
+            OffendedRule::MissingEntry => "has no FPL team to score with",
+        }
+    }
+}
+
+pub enum MatchActionError {
+    MatchNotFound,
+    UserNotFound,
+    NotOpen,
+    Full,
+    OwnMatch,
+    NotOwner,
+    NotInvited,
+    NotChallenged,
+    DeadlinePassed,
+    NotEnoughDCoin,
+    OutOfRatingBand,
+    Database(DbErr),
+}
+
+impl From<DebitError> for MatchActionError {
+    fn from(err: DebitError) -> Self {
+        match err {
+            DebitError::NotEnoughDCoin => Self::NotEnoughDCoin,
+            DebitError::Database(err) => Self::Database(err),
+        }
+    }
+}
+
+impl From<DbErr> for MatchActionError {
+    fn from(err: DbErr) -> Self {
+        Self::Database(err)
+    }
+}
+
+#[derive(Serialize, Clone, Copy)]
+pub enum CancelReason {
+    DeadlinePassed,
+    CancelledByOwner,
+    ChallengeDeclined,
+    ChallengeExpired,
+}
+
+impl CancelReason {
+    /// `metadata` of the match with the reason added, whatever else it holds is kept.
+    pub fn annotate(&self, metadata: &serde_json::Value) -> serde_json::Value {
+        let mut metadata = match metadata {
+            serde_json::Value::Object(fields) => fields.clone(),
+            _ => serde_json::Map::new(),
+        };
+
+        metadata.insert("cancel_reason".to_owned(), serde_json::json!(self));
+
+        metadata.into()
+    }
+
+    pub fn message(&self, match_id: i32) -> String {
+        match self {
+            CancelReason::DeadlinePassed => format!(
+                "Nobody joined the match {} before the deadline, your bet has been refunded",
+                match_id
+            ),
+            CancelReason::CancelledByOwner => format!(
+                "You have cancelled the match {}, your bet has been refunded",
+                match_id
+            ),
+            CancelReason::ChallengeDeclined => format!(
+                "Your challenge on the match {} has been declined, your bet has been refunded",
+                match_id
+            ),
+            CancelReason::ChallengeExpired => format!(
+                "Your challenge on the match {} was not accepted before the deadline, your bet has been refunded",
+                match_id
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER_ID: i32 = 1;
+    const OPPONENT_ID: i32 = 2;
+
+    fn settlement(
+        owner_point: i32,
+        opponent_point: i32,
+        owner_offended_rule: Option<OffendedRule>,
+        opponent_offended_rule: Option<OffendedRule>,
+    ) -> MatchSettlement {
+        MatchSettlement {
+            owner_point,
+            opponent_point,
+            owner_offended_rule,
+            opponent_offended_rule,
+        }
+    }
+
+    #[test]
+    fn higher_point_wins() {
+        assert_eq!(
+            settlement(60, 50, None, None).winner_id(OWNER_ID, OPPONENT_ID),
+            Some(OWNER_ID)
+        );
+        assert_eq!(
+            settlement(50, 60, None, None).winner_id(OWNER_ID, OPPONENT_ID),
+            Some(OPPONENT_ID)
+        );
+    }
+
+    #[test]
+    fn equal_points_draw() {
+        assert_eq!(
+            settlement(55, 55, None, None).winner_id(OWNER_ID, OPPONENT_ID),
+            None
+        );
+    }
+
+    #[test]
+    fn offender_forfeits_whatever_the_points() {
+        let owner_offended = settlement(90, 10, Some(OffendedRule::Transferring), None);
+
+        assert_eq!(
+            owner_offended.winner_id(OWNER_ID, OPPONENT_ID),
+            Some(OPPONENT_ID)
+        );
+        assert_eq!(
+            owner_offended.forfeited_rule(OWNER_ID, OPPONENT_ID),
+            Some(&OffendedRule::Transferring)
+        );
+
+        let opponent_offended = settlement(10, 90, None, Some(OffendedRule::MissingEntry));
+
+        assert_eq!(
+            opponent_offended.winner_id(OWNER_ID, OPPONENT_ID),
+            Some(OWNER_ID)
+        );
+        assert_eq!(
+            opponent_offended.forfeited_rule(OWNER_ID, OWNER_ID),
+            Some(&OffendedRule::MissingEntry)
+        );
+    }
+
+    #[test]
+    fn both_offending_draw() {
+        let both_offended = settlement(
+            90,
+            10,
+            Some(OffendedRule::UsingChip),
+            Some(OffendedRule::Transferring),
+        );
+
+        assert_eq!(both_offended.winner_id(OWNER_ID, OPPONENT_ID), None);
+    }
+
+    #[test]
+    fn no_forfeit_on_a_normal_win() {
+        assert_eq!(
+            settlement(60, 50, None, None).forfeited_rule(OWNER_ID, OWNER_ID),
+            None
+        );
+    }
+
+    #[test]
+    fn cancel_reason_keeps_the_other_metadata() {
+        let metadata = serde_json::json!({ "invite_code": "abc" });
+
+        assert_eq!(
+            CancelReason::ChallengeDeclined.annotate(&metadata),
+            serde_json::json!({ "invite_code": "abc", "cancel_reason": "ChallengeDeclined" })
+        );
+        assert_eq!(
+            CancelReason::DeadlinePassed.annotate(&serde_json::Value::Null),
+            serde_json::json!({ "cancel_reason": "DeadlinePassed" })
+        );
+    }
+}
@@ -1,2 +1,20 @@
+mod event_status;
+mod leaderboard;
 mod r#match;
+mod payment;
+mod promotion;
+mod rating;
+mod reconciliation;
+mod stats;
+mod tournament;
+mod transaction;
+pub use event_status::*;
+pub use leaderboard::*;
+pub use payment::*;
+pub use promotion::*;
 pub use r#match::*;
+pub use rating::*;
+pub use reconciliation::*;
+pub use stats::*;
+pub use tournament::*;
+pub use transaction::*;
@@ -0,0 +1,69 @@
+use crate::entities::sea_orm_active_enums::PaymentStatus;
+use sea_orm::DbErr;
+use services::payment::PaymentOutcome;
+
+pub enum PaymentActionError {
+    PaymentNotFound,
+    ProviderMismatch,
+    Database(DbErr),
+}
+
+impl From<DbErr> for PaymentActionError {
+    fn from(err: DbErr) -> Self {
+        Self::Database(err)
+    }
+}
+
+impl PaymentStatus {
+    /// The status a webhook with `outcome` moves the payment to, `None` when it must be left alone.
+    ///
+    /// A succeeded payment is final, a failed one can still succeed on a retried charge.
+    pub fn settle(&self, outcome: PaymentOutcome) -> Option<PaymentStatus> {
+        match (self, outcome) {
+            (PaymentStatus::Succeeded, _) => None,
+            (_, PaymentOutcome::Succeeded) => Some(PaymentStatus::Succeeded),
+            (PaymentStatus::Pending, PaymentOutcome::Failed) => Some(PaymentStatus::Failed),
+            (PaymentStatus::Failed, PaymentOutcome::Failed) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending_payment_settles_either_way() {
+        assert_eq!(
+            PaymentStatus::Pending.settle(PaymentOutcome::Succeeded),
+            Some(PaymentStatus::Succeeded)
+        );
+        assert_eq!(
+            PaymentStatus::Pending.settle(PaymentOutcome::Failed),
+            Some(PaymentStatus::Failed)
+        );
+    }
+
+    #[test]
+    fn succeeded_payment_is_never_credited_again() {
+        assert_eq!(
+            PaymentStatus::Succeeded.settle(PaymentOutcome::Succeeded),
+            None
+        );
+        assert_eq!(
+            PaymentStatus::Succeeded.settle(PaymentOutcome::Failed),
+            None
+        );
+    }
+
+    #[test]
+    fn failed_payment_is_credited_once_it_succeeds() {
+        let status = PaymentStatus::Pending
+            .settle(PaymentOutcome::Failed)
+            .unwrap();
+        let status = status.settle(PaymentOutcome::Succeeded).unwrap();
+
+        assert_eq!(status, PaymentStatus::Succeeded);
+        assert_eq!(status.settle(PaymentOutcome::Succeeded), None);
+    }
+}
@@ -0,0 +1,17 @@
+use sea_orm::DbErr;
+
+pub enum PromotionActionError {
+    CouponNotFound,
+    CouponCodeTaken,
+    CouponExpired,
+    CouponExhausted,
+    AlreadyRedeemed,
+    UserNotFound,
+    Database(DbErr),
+}
+
+impl From<DbErr> for PromotionActionError {
+    fn from(err: DbErr) -> Self {
+        Self::Database(err)
+    }
+}
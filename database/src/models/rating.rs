@@ -0,0 +1,51 @@
+use sea_orm::FromQueryResult;
+use serde::Serialize;
+
+/// Rating of a player who has not settled a rated match in the season yet.
+pub const INITIAL_RATING: i32 = 1200;
+
+const K_FACTOR: f64 = 32.0;
+
+#[derive(Serialize, FromQueryResult)]
+pub struct RatingOnLadder {
+    pub user_id: i32,
+    pub fpl_id: Option<i32>,
+    pub name: Option<String>,
+    pub rating: i32,
+    pub games: i32,
+}
+
+/// Elo change of a player scoring `score` against `opponent_rating`, 1 for a win, 0.5 for a draw, 0 for a loss.
+pub fn rating_change(rating: i32, opponent_rating: i32, score: f64) -> i32 {
+    let expected = 1.0 / (1.0 + 10f64.powf(f64::from(opponent_rating - rating) / 400.0));
+
+    (K_FACTOR * (score - expected)).round() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn even_players_move_half_the_k_factor() {
+        assert_eq!(rating_change(1200, 1200, 1.0), 16);
+        assert_eq!(rating_change(1200, 1200, 0.0), -16);
+        assert_eq!(rating_change(1200, 1200, 0.5), 0);
+    }
+
+    #[test]
+    fn upsets_move_more_than_expected_wins() {
+        let upset = rating_change(1000, 1400, 1.0);
+        let expected_win = rating_change(1400, 1000, 1.0);
+
+        assert!(upset > 16 && upset <= 32);
+        assert!((0..16).contains(&expected_win));
+        assert_eq!(upset + rating_change(1400, 1000, 0.0), 0);
+    }
+
+    #[test]
+    fn draw_favours_the_weaker_player() {
+        assert!(rating_change(1000, 1400, 0.5) > 0);
+        assert!(rating_change(1400, 1000, 0.5) < 0);
+    }
+}
@@ -0,0 +1,17 @@
+use sea_orm::FromQueryResult;
+use serde::Serialize;
+
+#[derive(Serialize, FromQueryResult, Clone)]
+pub struct BalanceDiscrepancy {
+    pub user_id: i32,
+    pub d_coin: i32,
+    /// Sum of the user's transactions, credits minus debits.
+    pub ledger_balance: i64,
+}
+
+impl BalanceDiscrepancy {
+    /// What the ledger misses to match the balance, negative when the ledger is ahead.
+    pub fn difference(&self) -> i64 {
+        i64::from(self.d_coin) - self.ledger_balance
+    }
+}
@@ -0,0 +1,322 @@
+use crate::entities::{
+    r#match,
+    sea_orm_active_enums::{ChipRule, TransferRule},
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// How many opponents the head-to-head records keep, the most played first.
+const HEAD_TO_HEAD_LIMIT: usize = 10;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum MatchResult {
+    Win,
+    Draw,
+    Loss,
+}
+
+#[derive(Serialize, Default)]
+pub struct Record {
+    pub games: i32,
+    pub wins: i32,
+    pub draws: i32,
+    pub losses: i32,
+}
+
+impl Record {
+    fn add(&mut self, result: MatchResult) {
+        self.games += 1;
+
+        match result {
+            MatchResult::Win => self.wins += 1,
+            MatchResult::Draw => self.draws += 1,
+            MatchResult::Loss => self.losses += 1,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct RecordByTransferRule {
+    pub transfer_rule: TransferRule,
+
+    #[serde(flatten)]
+    pub record: Record,
+}
+
+#[derive(Serialize)]
+pub struct RecordByChipRule {
+    pub chip_rule: ChipRule,
+
+    #[serde(flatten)]
+    pub record: Record,
+}
+
+#[derive(Serialize)]
+pub struct HeadToHead {
+    pub opponent_id: i32,
+
+    #[serde(flatten)]
+    pub record: Record,
+}
+
+#[derive(Serialize)]
+pub struct Streak {
+    pub result: MatchResult,
+    pub length: i32,
+}
+
+#[derive(Serialize)]
+pub struct UserStats {
+    #[serde(flatten)]
+    pub record: Record,
+    /// d_coin won minus d_coin lost.
+    pub net_profit: i32,
+    pub average_point: f64,
+    pub by_transfer_rule: Vec<RecordByTransferRule>,
+    pub by_chip_rule: Vec<RecordByChipRule>,
+    pub head_to_head: Vec<HeadToHead>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streak: Option<Streak>,
+}
+
+impl UserStats {
+    /// Compute the stats from the finished matches `user_id` played, in the order they settled.
+    pub fn from_matches(user_id: i32, matches: &[r#match::Model]) -> Self {
+        let mut record = Record::default();
+        let mut net_profit = 0;
+        let mut total_point = 0;
+        let mut by_transfer_rule: Vec<RecordByTransferRule> = vec![];
+        let mut by_chip_rule: Vec<RecordByChipRule> = vec![];
+        let mut head_to_head: HashMap<i32, Record> = HashMap::new();
+        let mut streak: Option<Streak> = None;
+
+        for r#match in matches {
+            let Some(opponent_id) = r#match.opponent_id else {
+                continue;
+            };
+
+            let (point, opponent_id) = if r#match.owner_id == user_id {
+                (r#match.owner_point, opponent_id)
+            } else {
+                (r#match.opponent_point, r#match.owner_id)
+            };
+
+            let result = match r#match.winner_id {
+                Some(winner_id) if winner_id == user_id => MatchResult::Win,
+                Some(_) => MatchResult::Loss,
+                None => MatchResult::Draw,
+            };
+
+            record.add(result);
+            total_point += point;
+            net_profit += match result {
+                MatchResult::Win => r#match.bet_amount,
+                MatchResult::Draw => 0,
+                MatchResult::Loss => -r#match.bet_amount,
+            };
+
+            match by_transfer_rule
+                .iter_mut()
+                .find(|by_rule| by_rule.transfer_rule == r#match.transfer_rule)
+            {
+                Some(by_rule) => by_rule.record.add(result),
+                None => {
+                    let mut rule_record = Record::default();
+                    rule_record.add(result);
+                    by_transfer_rule.push(RecordByTransferRule {
+                        transfer_rule: r#match.transfer_rule.clone(),
+                        record: rule_record,
+                    });
+                }
+            }
+
+            match by_chip_rule
+                .iter_mut()
+                .find(|by_rule| by_rule.chip_rule == r#match.chip_rule)
+            {
+                Some(by_rule) => by_rule.record.add(result),
+                None => {
+                    let mut rule_record = Record::default();
+                    rule_record.add(result);
+                    by_chip_rule.push(RecordByChipRule {
+                        chip_rule: r#match.chip_rule.clone(),
+                        record: rule_record,
+                    });
+                }
+            }
+
+            head_to_head.entry(opponent_id).or_default().add(result);
+
+            streak = match streak {
+                Some(Streak {
+                    result: last,
+                    length,
+                }) if last == result => Some(Streak {
+                    result,
+                    length: length + 1,
+                }),
+                _ => Some(Streak { result, length: 1 }),
+            };
+        }
+
+        let mut head_to_head: Vec<HeadToHead> = head_to_head
+            .into_iter()
+            .map(|(opponent_id, record)| HeadToHead {
+                opponent_id,
+                record,
+            })
+            .collect();
+        head_to_head.sort_by_key(|h2h| (-h2h.record.games, h2h.opponent_id));
+        head_to_head.truncate(HEAD_TO_HEAD_LIMIT);
+
+        let average_point = if record.games == 0 {
+            0.0
+        } else {
+            f64::from(total_point) / f64::from(record.games)
+        };
+
+        Self {
+            record,
+            net_profit,
+            average_point,
+            by_transfer_rule,
+            by_chip_rule,
+            head_to_head,
+            // a draw breaks a streak
+            streak: streak.filter(|streak| streak.result != MatchResult::Draw),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::sea_orm_active_enums::MatchStatus;
+
+    const USER_ID: i32 = 1;
+
+    /// A finished match of `USER_ID` as the owner, `winner_id` is None on a draw.
+    fn finished(
+        opponent_id: i32,
+        owner_point: i32,
+        opponent_point: i32,
+        winner_id: Option<i32>,
+    ) -> r#match::Model {
+        r#match::Model {
+            id: 0,
+            season: "2024-2025".to_owned(),
+            is_private: false,
+            created_date: chrono::Utc::now().fixed_offset(),
+            status: MatchStatus::Finished,
+            gameweek: 10,
+            end_gameweek: None,
+            bet_amount: 100,
+            transfer_rule: TransferRule::NoLimit,
+            chip_rule: ChipRule::All,
+            owner_id: USER_ID,
+            is_draw: winner_id.is_none(),
+            is_matched: true,
+            matched_at: None,
+            metadata: serde_json::json!({}),
+            opponent_id: Some(opponent_id),
+            opponent_point,
+            owner_point,
+            winner_id,
+            owner_offended_rule: None,
+            opponent_offended_rule: None,
+            min_week_started: None,
+            invite_code: None,
+            challenged_id: None,
+            tournament_id: None,
+            tournament_round: None,
+            is_rated: false,
+            min_rating: None,
+            max_rating: None,
+        }
+    }
+
+    fn win(opponent_id: i32) -> r#match::Model {
+        finished(opponent_id, 60, 50, Some(USER_ID))
+    }
+
+    fn loss(opponent_id: i32) -> r#match::Model {
+        finished(opponent_id, 50, 60, Some(opponent_id))
+    }
+
+    fn draw(opponent_id: i32) -> r#match::Model {
+        finished(opponent_id, 55, 55, None)
+    }
+
+    #[test]
+    fn record_and_profit() {
+        let stats = UserStats::from_matches(USER_ID, &[win(2), win(3), loss(2), draw(4)]);
+
+        assert_eq!(
+            (
+                stats.record.games,
+                stats.record.wins,
+                stats.record.draws,
+                stats.record.losses
+            ),
+            (4, 2, 1, 1)
+        );
+        assert_eq!(stats.net_profit, 100);
+        assert_eq!(stats.average_point, (60.0 + 60.0 + 50.0 + 55.0) / 4.0);
+    }
+
+    #[test]
+    fn points_are_read_from_the_user_side() {
+        let mut as_opponent = win(2);
+        as_opponent.owner_id = 2;
+        as_opponent.opponent_id = Some(USER_ID);
+        as_opponent.owner_point = 40;
+        as_opponent.opponent_point = 70;
+
+        let stats = UserStats::from_matches(USER_ID, &[as_opponent]);
+
+        assert_eq!(stats.average_point, 70.0);
+        assert_eq!(stats.head_to_head[0].opponent_id, 2);
+    }
+
+    #[test]
+    fn streak_counts_the_latest_results() {
+        let stats = UserStats::from_matches(USER_ID, &[win(2), loss(3), loss(4), loss(2)]);
+        let streak = stats.streak.unwrap();
+
+        assert!(streak.result == MatchResult::Loss);
+        assert_eq!(streak.length, 3);
+    }
+
+    #[test]
+    fn draw_breaks_the_streak() {
+        let stats = UserStats::from_matches(USER_ID, &[win(2), win(3), draw(4)]);
+
+        assert!(stats.streak.is_none());
+
+        let stats = UserStats::from_matches(USER_ID, &[draw(4), win(2)]);
+
+        assert_eq!(stats.streak.unwrap().length, 1);
+    }
+
+    #[test]
+    fn head_to_head_lists_frequent_opponents_first() {
+        let stats = UserStats::from_matches(USER_ID, &[win(3), win(2), loss(2), draw(2)]);
+        let opponents: Vec<(i32, i32)> = stats
+            .head_to_head
+            .iter()
+            .map(|h2h| (h2h.opponent_id, h2h.record.games))
+            .collect();
+
+        assert_eq!(opponents, vec![(2, 3), (3, 1)]);
+    }
+
+    #[test]
+    fn no_matches_no_stats() {
+        let stats = UserStats::from_matches(USER_ID, &[]);
+
+        assert_eq!(stats.record.games, 0);
+        assert_eq!(stats.average_point, 0.0);
+        assert!(stats.streak.is_none());
+    }
+}
@@ -0,0 +1,27 @@
+use super::DebitError;
+use sea_orm::DbErr;
+
+pub enum TournamentActionError {
+    TournamentNotFound,
+    UserNotFound,
+    RegistrationClosed,
+    Full,
+    AlreadyRegistered,
+    NotEnoughDCoin,
+    Database(DbErr),
+}
+
+impl From<DebitError> for TournamentActionError {
+    fn from(err: DebitError) -> Self {
+        match err {
+            DebitError::NotEnoughDCoin => Self::NotEnoughDCoin,
+            DebitError::Database(err) => Self::Database(err),
+        }
+    }
+}
+
+impl From<DbErr> for TournamentActionError {
+    fn from(err: DbErr) -> Self {
+        Self::Database(err)
+    }
+}
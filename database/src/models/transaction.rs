@@ -0,0 +1,69 @@
+use crate::entities::sea_orm_active_enums::{TransactionFlag, TransactionType};
+use sea_orm::{prelude::DateTimeWithTimeZone, DbErr, FromQueryResult};
+use serde::Serialize;
+
+pub enum DebitError {
+    NotEnoughDCoin,
+    Database(DbErr),
+}
+
+impl From<DbErr> for DebitError {
+    /// `update_d_coin` refuses a debit that would overdraw the balance with `RecordNotUpdated`.
+    fn from(err: DbErr) -> Self {
+        match err {
+            DbErr::RecordNotUpdated => Self::NotEnoughDCoin,
+            err => Self::Database(err),
+        }
+    }
+}
+
+#[derive(Serialize, FromQueryResult)]
+pub struct TransactionWithBalance {
+    pub id: i32,
+    pub created_date: DateTimeWithTimeZone,
+    pub flag: TransactionFlag,
+    pub r#type: TransactionType,
+    pub d_coin: i32,
+    pub message: String,
+    pub metadata: serde_json::Value,
+    /// Sum of the owner's ledger up to and including this transaction.
+    pub balance: i64,
+}
+
+impl TransactionWithBalance {
+    pub fn signed_d_coin(&self) -> i64 {
+        match self.flag {
+            TransactionFlag::Up => i64::from(self.d_coin),
+            TransactionFlag::Down => -i64::from(self.d_coin),
+        }
+    }
+}
+
+/// Transactions are returned newest first, `before_id` is the keyset cursor.
+#[derive(Default)]
+pub struct FindTransactionsParams {
+    pub owner_id: i32,
+    pub r#type: Option<TransactionType>,
+    pub flag: Option<TransactionFlag>,
+    pub from: Option<DateTimeWithTimeZone>,
+    pub to: Option<DateTimeWithTimeZone>,
+    pub before_id: Option<i32>,
+    pub take: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refused_debit_is_not_enough_d_coin() {
+        assert!(matches!(
+            DebitError::from(DbErr::RecordNotUpdated),
+            DebitError::NotEnoughDCoin
+        ));
+        assert!(matches!(
+            DebitError::from(DbErr::RecordNotFound("user".to_owned())),
+            DebitError::Database(_)
+        ));
+    }
+}
@@ -1,16 +1,26 @@
-use crate::entities::{event_status, prelude::EventStatus};
+use crate::{
+    entities::{
+        event_status,
+        prelude::{EventStatus, Season},
+        season,
+    },
+    models::settleable_events,
+};
 use sea_orm::{
-    sea_query::OnConflict, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
+    sea_query::{OnConflict, Query, SimpleExpr},
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
 };
 use services::fantasy::bootstrap;
 
 pub async fn update_events(
     db: &DatabaseConnection,
+    season: &str,
     events: Vec<bootstrap::Event>,
 ) -> Result<(), sea_orm::error::DbErr> {
     let models: Vec<event_status::ActiveModel> = events
         .into_iter()
         .map(|event| event_status::ActiveModel {
+            season: Set(season.to_owned()),
             gameweek: Set(event.id),
             name: Set(event.name),
             average_entry_score: Set(event.average_entry_score),
@@ -24,13 +34,12 @@ pub async fn update_events(
             is_previous: Set(event.is_previous),
             deadline_time_epoch: Set(event.deadline_time_epoch),
             highest_scoring_entry: Set(event.highest_scoring_entry.unwrap_or_default()),
-            ..Default::default()
         })
         .collect();
 
     EventStatus::insert_many(models)
         .on_conflict(
-            OnConflict::column(event_status::Column::Gameweek)
+            OnConflict::columns([event_status::Column::Season, event_status::Column::Gameweek])
                 .update_columns([
                     event_status::Column::AverageEntryScore,
                     event_status::Column::DataChecked,
@@ -50,20 +59,30 @@ pub async fn update_events(
         .map(|_| ())
 }
 
-pub async fn find_finished_previous_event(
+/// See [`settleable_events`], the last gameweek is settled without becoming previous.
+pub async fn find_settleable_events(
     db: &DatabaseConnection,
-) -> Result<Option<event_status::Model>, sea_orm::error::DbErr> {
-    EventStatus::find()
-        .filter(event_status::Column::IsPrevious.eq(true))
-        .filter(event_status::Column::Finished.eq(true))
-        .one(db)
-        .await
+) -> Result<Vec<event_status::Model>, sea_orm::error::DbErr> {
+    let events = EventStatus::find()
+        .filter(in_current_season())
+        .filter(
+            Condition::any()
+                .add(event_status::Column::IsPrevious.eq(true))
+                .add(event_status::Column::IsCurrent.eq(true))
+                .add(event_status::Column::IsNext.eq(true)),
+        )
+        .order_by_asc(event_status::Column::Gameweek)
+        .all(db)
+        .await?;
+
+    Ok(settleable_events(&events).into_iter().cloned().collect())
 }
 
 pub async fn find_next_event(
     db: &DatabaseConnection,
 ) -> Result<Option<event_status::Model>, sea_orm::error::DbErr> {
     EventStatus::find()
+        .filter(in_current_season())
         .filter(event_status::Column::IsNext.eq(true))
         .one(db)
         .await
@@ -73,7 +92,28 @@ pub async fn find_current_event(
     db: &DatabaseConnection,
 ) -> Result<Option<event_status::Model>, sea_orm::error::DbErr> {
     EventStatus::find()
+        .filter(in_current_season())
         .filter(event_status::Column::IsCurrent.eq(true))
         .one(db)
         .await
 }
+
+pub async fn find_event(
+    db: &DatabaseConnection,
+    season: &str,
+    gameweek: i32,
+) -> Result<Option<event_status::Model>, sea_orm::error::DbErr> {
+    EventStatus::find_by_id((season.to_owned(), gameweek))
+        .one(db)
+        .await
+}
+
+fn in_current_season() -> SimpleExpr {
+    event_status::Column::Season.in_subquery(
+        Query::select()
+            .column(season::Column::Name)
+            .from(Season)
+            .and_where(season::Column::IsCurrent.eq(true))
+            .to_owned(),
+    )
+}
@@ -0,0 +1,158 @@
+use crate::{
+    entities::{
+        prelude::{Match, User},
+        r#match,
+        sea_orm_active_enums::MatchStatus,
+        user,
+    },
+    models::{FindLeaderboardParams, LeaderboardEntry, LeaderboardMetric},
+};
+use sea_orm::{
+    sea_query::{Alias, Expr, Func, Query, SelectStatement, UnionType},
+    ColumnTrait, ConnectionTrait, DatabaseConnection, FromQueryResult, Order,
+};
+
+/// Rank the players on their settled matches, both sides of a match count for their player.
+pub async fn find_leaderboard(
+    db: &DatabaseConnection,
+    FindLeaderboardParams {
+        metric,
+        season,
+        from_gameweek,
+        to_gameweek,
+        min_games,
+        page,
+        take,
+    }: FindLeaderboardParams,
+) -> Result<(Vec<LeaderboardEntry>, u64), sea_orm::error::DbErr> {
+    let participations = |side: r#match::Column| {
+        let mut query = Query::select();
+
+        query
+            .expr_as(Expr::col(side), Alias::new("user_id"))
+            .columns([
+                r#match::Column::BetAmount,
+                r#match::Column::WinnerId,
+                r#match::Column::IsDraw,
+            ])
+            .from(Match)
+            .and_where(r#match::Column::Status.eq(MatchStatus::Finished))
+            .and_where(side.is_not_null());
+
+        if let Some(season) = season.clone() {
+            query.and_where(r#match::Column::Season.eq(season));
+        }
+
+        if let Some(from_gameweek) = from_gameweek {
+            query.and_where(r#match::Column::Gameweek.gte(from_gameweek));
+        }
+
+        if let Some(to_gameweek) = to_gameweek {
+            query.and_where(
+                Expr::expr(Func::coalesce([
+                    Expr::col(r#match::Column::EndGameweek).into(),
+                    Expr::col(r#match::Column::Gameweek).into(),
+                ]))
+                .lte(to_gameweek),
+            );
+        }
+
+        query
+    };
+
+    let mut union = participations(r#match::Column::OwnerId);
+    union.union(UnionType::All, participations(r#match::Column::OpponentId));
+
+    let participation = Alias::new("participation");
+
+    let mut leaderboard = Query::select();
+    leaderboard
+        .column((participation.clone(), Alias::new("user_id")))
+        .columns([(User, user::Column::FplId), (User, user::Column::Name)])
+        .expr_as(Expr::cust("COUNT(*)"), Alias::new("games"))
+        .expr_as(
+            Expr::cust(r#"COUNT(*) FILTER (WHERE "winner_id" = "user_id")"#),
+            Alias::new("wins"),
+        )
+        .expr_as(
+            Expr::cust(r#"COUNT(*) FILTER (WHERE "is_draw")"#),
+            Alias::new("draws"),
+        )
+        .expr_as(
+            Expr::cust(r#"COUNT(*) FILTER (WHERE "winner_id" <> "user_id")"#),
+            Alias::new("losses"),
+        )
+        .expr_as(
+            Expr::cust(
+                r#"(COUNT(*) FILTER (WHERE "winner_id" = "user_id"))::float8 / COUNT(*)"#,
+            ),
+            Alias::new("win_rate"),
+        )
+        .expr_as(
+            Expr::cust(
+                r#"COALESCE(SUM(CASE WHEN "winner_id" = "user_id" THEN "bet_amount" WHEN "winner_id" <> "user_id" THEN -"bet_amount" ELSE 0 END), 0)::int8"#,
+            ),
+            Alias::new("profit"),
+        )
+        .expr_as(
+            Expr::cust(r#"COALESCE(SUM("bet_amount"), 0)::int8"#),
+            Alias::new("volume"),
+        )
+        .from_subquery(union, participation.clone())
+        .inner_join(
+            User,
+            Expr::col((User, user::Column::Id)).equals((participation.clone(), Alias::new("user_id"))),
+        )
+        .group_by_col((participation.clone(), Alias::new("user_id")))
+        .group_by_col((User, user::Column::FplId))
+        .group_by_col((User, user::Column::Name))
+        .and_having(Expr::cust_with_values("COUNT(*) >= $1", [min_games]));
+
+    let total = count(db, leaderboard.clone()).await?;
+
+    for order_by in metric_order(metric) {
+        leaderboard.order_by(Alias::new(*order_by), Order::Desc);
+    }
+
+    leaderboard
+        .order_by((participation, Alias::new("user_id")), Order::Asc)
+        .offset((page - 1) * take)
+        .limit(take);
+
+    let entries =
+        LeaderboardEntry::find_by_statement(db.get_database_backend().build(&leaderboard))
+            .all(db)
+            .await?;
+
+    Ok((entries, total))
+}
+
+/// Columns ranking a metric, the later ones break ties.
+fn metric_order(metric: LeaderboardMetric) -> &'static [&'static str] {
+    match metric {
+        LeaderboardMetric::Profit => &["profit", "volume"],
+        LeaderboardMetric::Record => &["wins", "draws", "games"],
+        LeaderboardMetric::WinRate => &["win_rate", "games"],
+        LeaderboardMetric::Volume => &["volume", "games"],
+    }
+}
+
+async fn count(
+    db: &DatabaseConnection,
+    leaderboard: SelectStatement,
+) -> Result<u64, sea_orm::error::DbErr> {
+    let statement = Query::select()
+        .expr_as(Expr::cust("COUNT(*)"), Alias::new("total"))
+        .from_subquery(leaderboard, Alias::new("leaderboard"))
+        .to_owned();
+
+    let total: Option<i64> = match db
+        .query_one(db.get_database_backend().build(&statement))
+        .await?
+    {
+        Some(result) => result.try_get("", "total")?,
+        None => None,
+    };
+
+    Ok(total.unwrap_or_default() as u64)
+}
@@ -1,22 +1,31 @@
 use crate::{
     entities::{
-        prelude::{Match, Transaction},
+        event_status,
+        prelude::{EventStatus, Match, Transaction, User},
         r#match,
         sea_orm_active_enums::{MatchStatus, TransactionFlag, TransactionType},
         transaction,
     },
-    models::{FindMatchesParams, MatchWithOwnerOpponentAndWinner},
-    repositories::user_repository::update_d_coin,
+    models::{
+        CancelReason, FindMatchesParams, MatchActionError, MatchSettlement,
+        MatchWithOwnerOpponentAndWinner, INITIAL_RATING,
+    },
+    repositories::{
+        rating_repository,
+        user_repository::{debit_d_coin, update_d_coin},
+    },
 };
+use chrono::Utc;
 use sea_orm::{
-    sea_query::{Alias, Expr},
-    ColumnTrait, Condition, DatabaseConnection, EntityTrait, Iterable, Order, PaginatorTrait,
-    QueryFilter, QueryOrder, QuerySelect, QueryTrait, RelationTrait, SelectColumns, Set,
-    TransactionTrait,
+    sea_query::{Alias, Expr, Func, Query},
+    ColumnTrait, Condition, DatabaseConnection, DatabaseTransaction, EntityTrait, Iterable, Order,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait, RelationTrait, SelectColumns,
+    Set, TransactionTrait,
 };
 
 pub async fn update_all_next_round_to_live_by_gameweek(
     db: &DatabaseConnection,
+    season: &str,
     gameweek: i32,
 ) -> Result<(), sea_orm::error::DbErr> {
     let active_model = r#match::ActiveModel {
@@ -27,15 +36,18 @@ pub async fn update_all_next_round_to_live_by_gameweek(
     Match::update_many()
         .set(active_model)
         .filter(r#match::Column::Status.eq(MatchStatus::Next))
+        .filter(r#match::Column::Season.eq(season))
         .filter(r#match::Column::Gameweek.eq(gameweek))
+        .filter(r#match::Column::OpponentId.is_not_null())
         .exec_with_returning(db)
         .await?;
 
     Ok(())
 }
 
-pub async fn update_all_live_to_finished_by_gameweek(
+pub async fn update_all_unmatched_live_to_finished_by_gameweek(
     db: &DatabaseConnection,
+    season: &str,
     gameweek: i32,
 ) -> Result<(), sea_orm::error::DbErr> {
     let active_model = r#match::ActiveModel {
@@ -43,33 +55,216 @@ pub async fn update_all_live_to_finished_by_gameweek(
         ..Default::default()
     };
 
+    // Matched ones are finished by `settle_match` together with their payout
     Match::update_many()
         .set(active_model)
         .filter(r#match::Column::Status.eq(MatchStatus::Live))
-        .filter(r#match::Column::Gameweek.eq(gameweek))
+        .filter(r#match::Column::Season.eq(season))
+        .filter(last_gameweek().eq(gameweek))
+        .filter(r#match::Column::OpponentId.is_null())
         .exec_with_returning(db)
         .await?;
 
     Ok(())
 }
 
+/// Matches being played on `gameweek`, including the ones spanning over it.
+pub async fn find_all_matched_live_by_gameweek(
+    db: &DatabaseConnection,
+    season: &str,
+    gameweek: i32,
+) -> Result<Vec<r#match::Model>, sea_orm::error::DbErr> {
+    Match::find()
+        .filter(r#match::Column::Status.eq(MatchStatus::Live))
+        .filter(r#match::Column::Season.eq(season))
+        .filter(r#match::Column::Gameweek.lte(gameweek))
+        .filter(last_gameweek().gte(gameweek))
+        .filter(r#match::Column::OpponentId.is_not_null())
+        .order_by(r#match::Column::Id, Order::Asc)
+        .all(db)
+        .await
+}
+
+pub async fn find_all_matched_live_ending_at_gameweek(
+    db: &DatabaseConnection,
+    season: &str,
+    gameweek: i32,
+) -> Result<Vec<r#match::Model>, sea_orm::error::DbErr> {
+    Match::find()
+        .filter(r#match::Column::Status.eq(MatchStatus::Live))
+        .filter(r#match::Column::Season.eq(season))
+        .filter(last_gameweek().eq(gameweek))
+        .filter(r#match::Column::OpponentId.is_not_null())
+        .order_by(r#match::Column::Id, Order::Asc)
+        .all(db)
+        .await
+}
+
+pub async fn update_live_points(
+    db: &DatabaseConnection,
+    match_id: i32,
+    owner_point: i32,
+    opponent_point: i32,
+) -> Result<(), sea_orm::error::DbErr> {
+    Match::update_many()
+        .set(r#match::ActiveModel {
+            owner_point: Set(owner_point),
+            opponent_point: Set(opponent_point),
+            ..Default::default()
+        })
+        .filter(r#match::Column::Id.eq(match_id))
+        .filter(r#match::Column::Status.eq(MatchStatus::Live))
+        .exec(db)
+        .await
+        .map(|_| ())
+}
+
+pub async fn settle_match(
+    db: &DatabaseConnection,
+    match_id: i32,
+    settlement: MatchSettlement,
+) -> Result<(), sea_orm::error::DbErr> {
+    let txn = db.begin().await?;
+
+    // lock the match so it can never be paid out twice
+    let r#match = Match::find_by_id(match_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .filter(|r#match| r#match.status == MatchStatus::Live);
+
+    let Some((r#match, opponent_id)) = r#match.and_then(|r#match| {
+        r#match
+            .opponent_id
+            .map(|opponent_id| (r#match, opponent_id))
+    }) else {
+        return txn.rollback().await;
+    };
+
+    let winner_id = settlement.winner_id(r#match.owner_id, opponent_id);
+
+    // update result
+    Match::update_many()
+        .set(r#match::ActiveModel {
+            status: Set(MatchStatus::Finished),
+            owner_point: Set(settlement.owner_point),
+            opponent_point: Set(settlement.opponent_point),
+            owner_offended_rule: Set(settlement.owner_offended_rule.clone()),
+            opponent_offended_rule: Set(settlement.opponent_offended_rule.clone()),
+            winner_id: Set(winner_id),
+            is_draw: Set(winner_id.is_none()),
+            ..Default::default()
+        })
+        .filter(r#match::Column::Id.eq(match_id))
+        .exec(&txn)
+        .await?;
+
+    let metadata = serde_json::json!({
+        "match_id": match_id,
+        "on_gameweek": r#match.gameweek,
+        "end_gameweek": r#match.end_gameweek,
+        "owner_point": settlement.owner_point,
+        "opponent_point": settlement.opponent_point,
+        "owner_offended_rule": settlement.owner_offended_rule,
+        "opponent_offended_rule": settlement.opponent_offended_rule,
+    });
+
+    if r#match.is_rated {
+        rating_repository::update_ratings(
+            &txn,
+            &r#match.season,
+            r#match.owner_id,
+            opponent_id,
+            winner_id,
+        )
+        .await?;
+    }
+
+    // tournament matches have no bet, their prize is paid when the tournament finishes
+    if r#match.tournament_id.is_some() {
+        return txn.commit().await;
+    }
+
+    // pay out
+    let transactions = match winner_id {
+        Some(winner_id) => {
+            let reward = r#match.bet_amount * 2;
+
+            update_d_coin(&txn, winner_id, reward, TransactionFlag::Up).await?;
+
+            let message = match settlement.forfeited_rule(r#match.owner_id, winner_id) {
+                Some(rule) => format!(
+                    "You have won the match {} by forfeit, your opponent {}",
+                    match_id,
+                    rule.describe()
+                ),
+                None => format!("You have won the match {}", match_id),
+            };
+
+            vec![transaction::ActiveModel {
+                d_coin: Set(reward),
+                message: Set(message),
+                flag: Set(TransactionFlag::Up),
+                metadata: Set(metadata),
+                owner_id: Set(winner_id),
+                r#type: Set(TransactionType::WinMatch),
+                ..Default::default()
+            }]
+        }
+        None => {
+            let mut transactions = Vec::with_capacity(2);
+
+            for participant_id in [r#match.owner_id, opponent_id] {
+                update_d_coin(
+                    &txn,
+                    participant_id,
+                    r#match.bet_amount,
+                    TransactionFlag::Up,
+                )
+                .await?;
+
+                transactions.push(transaction::ActiveModel {
+                    d_coin: Set(r#match.bet_amount),
+                    message: Set(format!(
+                        "The match {} ended in a draw, your bet has been refunded",
+                        match_id
+                    )),
+                    flag: Set(TransactionFlag::Up),
+                    metadata: Set(metadata.clone()),
+                    owner_id: Set(participant_id),
+                    r#type: Set(TransactionType::Refund),
+                    ..Default::default()
+                });
+            }
+
+            transactions
+        }
+    };
+
+    Transaction::insert_many(transactions)
+        .exec_without_returning(&txn)
+        .await?;
+
+    txn.commit().await
+}
+
 pub async fn create_matches(
     db: &DatabaseConnection,
     creator_id: i32,
     matches: Vec<r#match::ActiveModel>,
     game_week: i32,
     total_d_coin: i32,
-) -> Result<(), sea_orm::error::DbErr> {
+) -> Result<(), MatchActionError> {
     let txn = db.begin().await?;
     let quantity = matches.len();
 
     // create matches
     Match::insert_many(matches)
-        .exec_without_returning(db)
+        .exec_without_returning(&txn)
         .await?;
 
     // collect d_coin
-    update_d_coin(db, creator_id, total_d_coin, TransactionFlag::Down).await?;
+    debit_d_coin(&txn, creator_id, total_d_coin).await?;
 
     // create transactions
     let metadata = serde_json::json!({
@@ -85,7 +280,7 @@ pub async fn create_matches(
         r#type: Set(TransactionType::CreateMatch),
         ..Default::default()
     })
-    .exec_without_returning(db)
+    .exec_without_returning(&txn)
     .await?;
 
     txn.commit().await?;
@@ -102,18 +297,31 @@ pub async fn find_matches(
         status,
         take,
         exclude_created_by,
+        exclude_private,
+        exclude_challenges,
+        challenged,
+        rated_only,
+        joinable_with_rating,
+        min_owner_rating,
+        max_owner_rating,
         season,
     }: FindMatchesParams,
 ) -> Result<(Vec<MatchWithOwnerOpponentAndWinner>, u64), sea_orm::error::DbErr> {
     let query_builder = Match::find()
         // Select partial match
         .select_only()
-        .columns(r#match::Column::iter().filter(|col| match col {
-            &r#match::Column::IsPrivate
-            | &r#match::Column::WinnerId
-            | &r#match::Column::OpponentId
-            | &r#match::Column::OwnerId => false,
-            _ => true,
+        .columns(r#match::Column::iter().filter(|col| {
+            !matches!(
+                col,
+                r#match::Column::IsPrivate
+                    | r#match::Column::InviteCode
+                    | r#match::Column::ChallengedId
+                    | r#match::Column::MinRating
+                    | r#match::Column::MaxRating
+                    | r#match::Column::WinnerId
+                    | r#match::Column::OpponentId
+                    | r#match::Column::OwnerId
+            )
         }))
         // Select partial owner
         .select_column_as(Expr::cust("owner.id"), "owner_id")
@@ -181,6 +389,37 @@ pub async fn find_matches(
         .apply_if(exclude_created_by, |query, exclude_created_by| {
             query.filter(r#match::Column::OwnerId.ne(exclude_created_by))
         })
+        .apply_if(exclude_private.then_some(false), |query, is_private| {
+            query.filter(r#match::Column::IsPrivate.eq(is_private))
+        })
+        .apply_if(exclude_challenges.then_some(()), |query, _| {
+            query.filter(r#match::Column::ChallengedId.is_null())
+        })
+        .apply_if(challenged, |query, challenged| {
+            query.filter(r#match::Column::ChallengedId.eq(challenged))
+        })
+        .apply_if(rated_only.then_some(true), |query, is_rated| {
+            query.filter(r#match::Column::IsRated.eq(is_rated))
+        })
+        .apply_if(joinable_with_rating, |query, rating| {
+            query
+                .filter(
+                    Condition::any()
+                        .add(r#match::Column::MinRating.is_null())
+                        .add(r#match::Column::MinRating.lte(rating)),
+                )
+                .filter(
+                    Condition::any()
+                        .add(r#match::Column::MaxRating.is_null())
+                        .add(r#match::Column::MaxRating.gte(rating)),
+                )
+        })
+        .apply_if(min_owner_rating, |query, min_owner_rating| {
+            query.filter(owner_rating().gte(min_owner_rating))
+        })
+        .apply_if(max_owner_rating, |query, max_owner_rating| {
+            query.filter(owner_rating().lte(max_owner_rating))
+        })
         .apply_if(season.clone(), |query, season| {
             query.filter(r#match::Column::Season.eq(season))
         })
@@ -211,17 +450,323 @@ pub async fn find_by_id(
         .await
 }
 
-pub async fn update_when_user_join_match(
+/// Finished matches `user_id` played, in the order they settled.
+pub async fn find_all_finished_by_participant(
+    db: &DatabaseConnection,
+    user_id: i32,
+    season: Option<String>,
+) -> Result<Vec<r#match::Model>, sea_orm::error::DbErr> {
+    Match::find()
+        .filter(r#match::Column::Status.eq(MatchStatus::Finished))
+        .filter(
+            Condition::any()
+                .add(r#match::Column::OwnerId.eq(user_id))
+                .add(r#match::Column::OpponentId.eq(user_id)),
+        )
+        .filter(r#match::Column::OpponentId.is_not_null())
+        .apply_if(season, |query, season| {
+            query.filter(r#match::Column::Season.eq(season))
+        })
+        .order_by(r#match::Column::Season, Order::Asc)
+        .order_by(last_gameweek(), Order::Asc)
+        .order_by(r#match::Column::Id, Order::Asc)
+        .all(db)
+        .await
+}
+
+pub async fn find_by_invite_code(
+    db: &DatabaseConnection,
+    invite_code: &str,
+) -> Result<Option<r#match::Model>, sea_orm::error::DbErr> {
+    Match::find()
+        .filter(r#match::Column::InviteCode.eq(invite_code))
+        .one(db)
+        .await
+}
+
+/// Private matches can only be joined with their invite code, challenges only by the challenged user.
+pub async fn join_match(
+    db: &DatabaseConnection,
+    match_id: i32,
+    user_id: i32,
+    invite_code: Option<&str>,
+) -> Result<(), MatchActionError> {
+    let txn = db.begin().await?;
+
+    // lock the match so only one user can take it
+    let r#match = Match::find_by_id(match_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(MatchActionError::MatchNotFound)?;
+
+    if r#match.status != MatchStatus::Next {
+        return Err(MatchActionError::NotOpen);
+    }
+
+    if r#match.opponent_id.is_some() {
+        return Err(MatchActionError::Full);
+    }
+
+    if r#match.owner_id == user_id {
+        return Err(MatchActionError::OwnMatch);
+    }
+
+    if r#match
+        .challenged_id
+        .is_some_and(|challenged_id| challenged_id != user_id)
+    {
+        return Err(MatchActionError::NotChallenged);
+    }
+
+    if r#match.is_private
+        && r#match.challenged_id.is_none()
+        && r#match.invite_code.as_deref() != invite_code
+    {
+        return Err(MatchActionError::NotInvited);
+    }
+
+    let event = EventStatus::find_by_id((r#match.season.clone(), r#match.gameweek))
+        .one(&txn)
+        .await?;
+
+    if event.is_none_or(|event| event.deadline_time <= Utc::now()) {
+        return Err(MatchActionError::DeadlinePassed);
+    }
+
+    if r#match.min_rating.is_some() || r#match.max_rating.is_some() {
+        let rating = rating_repository::find_rating(&txn, user_id, &r#match.season).await?;
+
+        if r#match.min_rating.is_some_and(|min| rating < min)
+            || r#match.max_rating.is_some_and(|max| rating > max)
+        {
+            return Err(MatchActionError::OutOfRatingBand);
+        }
+    }
+
+    // lock the opponent so concurrent joins can not spend the same d_coin
+    let user = User::find_by_id(user_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(MatchActionError::UserNotFound)?;
+
+    if user.d_coin < r#match.bet_amount {
+        return Err(MatchActionError::NotEnoughDCoin);
+    }
+
+    // fill the opponent
+    Match::update_many()
+        .set(r#match::ActiveModel {
+            opponent_id: Set(Some(user_id)),
+            is_matched: Set(true),
+            matched_at: Set(Some(Utc::now().fixed_offset())),
+            ..Default::default()
+        })
+        .filter(r#match::Column::Id.eq(match_id))
+        .exec(&txn)
+        .await?;
+
+    // collect d_coin
+    debit_d_coin(&txn, user_id, r#match.bet_amount).await?;
+
+    // create transaction
+    let metadata = serde_json::json!({
+        "match_id": match_id,
+        "on_gameweek": r#match.gameweek,
+    });
+    Transaction::insert(transaction::ActiveModel {
+        d_coin: Set(r#match.bet_amount),
+        message: Set(format!("You have joined the match {}", match_id)),
+        flag: Set(TransactionFlag::Down),
+        metadata: Set(metadata),
+        owner_id: Set(user_id),
+        r#type: Set(TransactionType::JoinMatch),
+        ..Default::default()
+    })
+    .exec_without_returning(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+pub async fn cancel_match(
+    db: &DatabaseConnection,
+    match_id: i32,
+    owner_id: i32,
+) -> Result<(), MatchActionError> {
+    let txn = db.begin().await?;
+
+    // lock the match so nobody can join while it is being cancelled
+    let r#match = Match::find_by_id(match_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(MatchActionError::MatchNotFound)?;
+
+    if r#match.owner_id != owner_id {
+        return Err(MatchActionError::NotOwner);
+    }
+
+    if r#match.status != MatchStatus::Next {
+        return Err(MatchActionError::NotOpen);
+    }
+
+    if r#match.opponent_id.is_some() {
+        return Err(MatchActionError::Full);
+    }
+
+    cancel_and_refund(&txn, &r#match, CancelReason::CancelledByOwner).await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+pub async fn decline_challenge(
     db: &DatabaseConnection,
     match_id: i32,
     user_id: i32,
+) -> Result<(), MatchActionError> {
+    let txn = db.begin().await?;
+
+    // lock the match so it can not be accepted while it is being declined
+    let r#match = Match::find_by_id(match_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(MatchActionError::MatchNotFound)?;
+
+    if r#match.challenged_id != Some(user_id) {
+        return Err(MatchActionError::NotChallenged);
+    }
+
+    if r#match.status != MatchStatus::Next {
+        return Err(MatchActionError::NotOpen);
+    }
+
+    if r#match.opponent_id.is_some() {
+        return Err(MatchActionError::Full);
+    }
+
+    cancel_and_refund(&txn, &r#match, CancelReason::ChallengeDeclined).await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+pub async fn void_unmatched_matches_past_deadline(
+    db: &DatabaseConnection,
 ) -> Result<(), sea_orm::error::DbErr> {
-    Match::update(r#match::ActiveModel {
-        opponent_id: Set(Some(user_id)),
+    let passed_gameweeks = Query::select()
+        .columns([event_status::Column::Season, event_status::Column::Gameweek])
+        .from(EventStatus)
+        .and_where(event_status::Column::DeadlineTime.lte(Utc::now()))
+        .to_owned();
+
+    let match_ids: Vec<i32> = Match::find()
+        .select_only()
+        .column(r#match::Column::Id)
+        .filter(r#match::Column::Status.eq(MatchStatus::Next))
+        .filter(r#match::Column::OpponentId.is_null())
+        .filter(
+            Expr::tuple([
+                Expr::col(r#match::Column::Season).into(),
+                Expr::col(r#match::Column::Gameweek).into(),
+            ])
+            .in_subquery(passed_gameweeks),
+        )
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    for match_id in match_ids {
+        let txn = db.begin().await?;
+
+        let r#match = Match::find_by_id(match_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .filter(|r#match| r#match.status == MatchStatus::Next && r#match.opponent_id.is_none());
+
+        // joined or cancelled in the meantime
+        let Some(r#match) = r#match else {
+            txn.rollback().await?;
+            continue;
+        };
+
+        let reason = match r#match.challenged_id {
+            Some(_) => CancelReason::ChallengeExpired,
+            None => CancelReason::DeadlinePassed,
+        };
+
+        cancel_and_refund(&txn, &r#match, reason).await?;
+
+        txn.commit().await?;
+    }
+
+    Ok(())
+}
+
+/// `r#match` must be locked by `txn`, its metadata is rewritten from the row read under the lock.
+async fn cancel_and_refund(
+    txn: &DatabaseTransaction,
+    r#match: &r#match::Model,
+    reason: CancelReason,
+) -> Result<(), sea_orm::error::DbErr> {
+    Match::update_many()
+        .set(r#match::ActiveModel {
+            status: Set(MatchStatus::Cancelled),
+            metadata: Set(reason.annotate(&r#match.metadata)),
+            ..Default::default()
+        })
+        .filter(r#match::Column::Id.eq(r#match.id))
+        .exec(txn)
+        .await?;
+
+    update_d_coin(
+        txn,
+        r#match.owner_id,
+        r#match.bet_amount,
+        TransactionFlag::Up,
+    )
+    .await?;
+
+    let metadata = serde_json::json!({
+        "match_id": r#match.id,
+        "on_gameweek": r#match.gameweek,
+        "cancel_reason": reason,
+    });
+    Transaction::insert(transaction::ActiveModel {
+        d_coin: Set(r#match.bet_amount),
+        message: Set(reason.message(r#match.id)),
+        flag: Set(TransactionFlag::Up),
+        metadata: Set(metadata),
+        owner_id: Set(r#match.owner_id),
+        r#type: Set(TransactionType::Refund),
         ..Default::default()
     })
-    .filter(r#match::Column::Id.eq(match_id))
-    .exec(db)
-    .await
-    .map(|_| ())
+    .exec_without_returning(txn)
+    .await?;
+
+    Ok(())
+}
+
+/// Gameweek a match ends on, single gameweek matches have no `end_gameweek`.
+fn last_gameweek() -> Expr {
+    Expr::expr(Func::coalesce([
+        Expr::col(r#match::Column::EndGameweek).into(),
+        Expr::col(r#match::Column::Gameweek).into(),
+    ]))
+}
+
+/// Season rating of the match owner, players without a rated match yet have the initial rating.
+fn owner_rating() -> Expr {
+    Expr::expr(Expr::cust_with_values(
+        r#"COALESCE((SELECT "user_rating"."rating" FROM "user_rating" WHERE "user_rating"."user_id" = "match"."owner_id" AND "user_rating"."season" = "match"."season"), $1)"#,
+        [INITIAL_RATING],
+    ))
 }
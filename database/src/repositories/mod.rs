@@ -1,3 +1,10 @@
 pub mod event_status_repository;
+pub mod leaderboard_repository;
 pub mod match_repository;
+pub mod payment_repository;
+pub mod promotion_repository;
+pub mod rating_repository;
+pub mod season_repository;
+pub mod tournament_repository;
+pub mod transaction_repository;
 pub mod user_repository;
@@ -0,0 +1,109 @@
+use crate::{
+    entities::{
+        payment,
+        prelude::{Payment, Transaction},
+        sea_orm_active_enums::{PaymentStatus, TransactionFlag, TransactionType},
+        transaction,
+    },
+    models::PaymentActionError,
+    repositories::user_repository::update_d_coin,
+};
+use chrono::Utc;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect, Set, TransactionTrait,
+};
+use services::payment::PaymentOutcome;
+
+pub async fn create_payment(
+    db: &DatabaseConnection,
+    data: payment::ActiveModel,
+) -> Result<(), sea_orm::error::DbErr> {
+    Payment::insert(data).exec_without_returning(db).await?;
+
+    Ok(())
+}
+
+/// Credit a succeeded payment, a payment is only ever credited once however often its webhook comes.
+///
+/// Returns whether this call credited it.
+pub async fn complete_payment(
+    db: &DatabaseConnection,
+    payment_id: &str,
+    provider: &str,
+    provider_reference: &str,
+) -> Result<bool, PaymentActionError> {
+    let txn = db.begin().await?;
+
+    // lock the payment so concurrent deliveries of the webhook wait for each other
+    let payment = Payment::find_by_id(payment_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(PaymentActionError::PaymentNotFound)?;
+
+    if payment.provider != provider {
+        return Err(PaymentActionError::ProviderMismatch);
+    }
+
+    let Some(status) = payment.status.settle(PaymentOutcome::Succeeded) else {
+        txn.rollback().await?;
+        return Ok(false);
+    };
+
+    Payment::update_many()
+        .set(payment::ActiveModel {
+            status: Set(status),
+            provider_reference: Set(Some(provider_reference.to_owned())),
+            completed_at: Set(Some(Utc::now().fixed_offset())),
+            ..Default::default()
+        })
+        .filter(payment::Column::Id.eq(payment_id))
+        .exec(&txn)
+        .await?;
+
+    update_d_coin(&txn, payment.user_id, payment.d_coin, TransactionFlag::Up).await?;
+
+    Transaction::insert(transaction::ActiveModel {
+        d_coin: Set(payment.d_coin),
+        message: Set(format!("You have purchased {} d_coin", payment.d_coin)),
+        flag: Set(TransactionFlag::Up),
+        metadata: Set(serde_json::json!({
+            "payment_id": payment.id,
+            "provider": payment.provider,
+            "provider_reference": provider_reference,
+            "amount": payment.amount,
+        })),
+        owner_id: Set(payment.user_id),
+        r#type: Set(TransactionType::Purchase),
+        ..Default::default()
+    })
+    .exec_without_returning(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(true)
+}
+
+/// A failed payment stays failed, but never overrides one already credited.
+pub async fn fail_payment(
+    db: &DatabaseConnection,
+    payment_id: &str,
+    provider: &str,
+    provider_reference: &str,
+) -> Result<(), sea_orm::error::DbErr> {
+    Payment::update_many()
+        .set(payment::ActiveModel {
+            status: Set(PaymentStatus::Failed),
+            provider_reference: Set(Some(provider_reference.to_owned())),
+            completed_at: Set(Some(Utc::now().fixed_offset())),
+            ..Default::default()
+        })
+        .filter(payment::Column::Id.eq(payment_id))
+        .filter(payment::Column::Provider.eq(provider))
+        .filter(payment::Column::Status.eq(PaymentStatus::Pending))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
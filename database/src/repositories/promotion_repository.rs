@@ -0,0 +1,209 @@
+use crate::{
+    entities::{
+        coupon, coupon_redemption,
+        prelude::{Coupon, CouponRedemption, Transaction, User, UserIdentity},
+        sea_orm_active_enums::{TransactionFlag, TransactionType},
+        transaction, user, user_identity,
+    },
+    models::PromotionActionError,
+    repositories::user_repository::update_d_coin,
+};
+use chrono::Utc;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect, Set,
+    SqlErr, TransactionTrait,
+};
+
+/// Insert a new user along with the identity they signed up with, crediting `signup_bonus`
+/// in the same transaction when it is positive.
+pub async fn create_user_with_signup_bonus(
+    db: &DatabaseConnection,
+    data: user::ActiveModel,
+    identity: user_identity::ActiveModel,
+    signup_bonus: i32,
+) -> Result<user::Model, sea_orm::error::DbErr> {
+    let txn = db.begin().await?;
+
+    let new_user = User::insert(data).exec_with_returning(&txn).await?;
+
+    UserIdentity::insert(user_identity::ActiveModel {
+        user_id: Set(new_user.id),
+        created_date: Set(Utc::now().into()),
+        ..identity
+    })
+    .exec_without_returning(&txn)
+    .await?;
+
+    if signup_bonus > 0 {
+        credit(
+            &txn,
+            new_user.id,
+            signup_bonus,
+            format!("Welcome! You have received {} d_coin", signup_bonus),
+            serde_json::json!({ "promotion": "signup_bonus" }),
+        )
+        .await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(User::find_by_id(new_user.id)
+        .one(db)
+        .await?
+        .unwrap_or(new_user))
+}
+
+pub async fn grant(
+    db: &DatabaseConnection,
+    user_id: i32,
+    d_coin: i32,
+    granted_by: i32,
+    reason: String,
+) -> Result<(), PromotionActionError> {
+    let txn = db.begin().await?;
+
+    User::find_by_id(user_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(PromotionActionError::UserNotFound)?;
+
+    credit(
+        &txn,
+        user_id,
+        d_coin,
+        format!("You have been granted {} d_coin: {}", d_coin, reason),
+        serde_json::json!({
+            "promotion": "grant",
+            "granted_by": granted_by,
+            "reason": reason,
+        }),
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+pub async fn create_coupon(
+    db: &DatabaseConnection,
+    data: coupon::ActiveModel,
+) -> Result<coupon::Model, PromotionActionError> {
+    Coupon::insert(data)
+        .exec_with_returning(db)
+        .await
+        .map_err(|err| match err.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => PromotionActionError::CouponCodeTaken,
+            _ => err.into(),
+        })
+}
+
+/// Each user can redeem a coupon once, while it is neither expired nor used up.
+pub async fn redeem_coupon(
+    db: &DatabaseConnection,
+    code: &str,
+    user_id: i32,
+) -> Result<coupon::Model, PromotionActionError> {
+    let txn = db.begin().await?;
+
+    // lock the coupon so concurrent redemptions can not exceed its limit
+    let coupon = Coupon::find()
+        .filter(coupon::Column::Code.eq(code))
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(PromotionActionError::CouponNotFound)?;
+
+    if coupon
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= Utc::now())
+    {
+        return Err(PromotionActionError::CouponExpired);
+    }
+
+    if coupon
+        .max_redemptions
+        .is_some_and(|max_redemptions| coupon.redemption_count >= max_redemptions)
+    {
+        return Err(PromotionActionError::CouponExhausted);
+    }
+
+    let redeemed = CouponRedemption::find()
+        .filter(coupon_redemption::Column::CouponId.eq(coupon.id))
+        .filter(coupon_redemption::Column::UserId.eq(user_id))
+        .one(&txn)
+        .await?;
+
+    if redeemed.is_some() {
+        return Err(PromotionActionError::AlreadyRedeemed);
+    }
+
+    User::find_by_id(user_id)
+        .one(&txn)
+        .await?
+        .ok_or(PromotionActionError::UserNotFound)?;
+
+    CouponRedemption::insert(coupon_redemption::ActiveModel {
+        coupon_id: Set(coupon.id),
+        user_id: Set(user_id),
+        redeemed_at: Set(Utc::now().fixed_offset()),
+        ..Default::default()
+    })
+    .exec_without_returning(&txn)
+    .await?;
+
+    Coupon::update_many()
+        .set(coupon::ActiveModel {
+            redemption_count: Set(coupon.redemption_count + 1),
+            ..Default::default()
+        })
+        .filter(coupon::Column::Id.eq(coupon.id))
+        .exec(&txn)
+        .await?;
+
+    credit(
+        &txn,
+        user_id,
+        coupon.d_coin,
+        format!(
+            "You have redeemed the coupon {} for {} d_coin",
+            coupon.code, coupon.d_coin
+        ),
+        serde_json::json!({
+            "promotion": "coupon",
+            "coupon_id": coupon.id,
+            "code": coupon.code,
+        }),
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(coupon)
+}
+
+/// Every promotional credit is an `Event` transaction.
+async fn credit<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+    d_coin: i32,
+    message: String,
+    metadata: serde_json::Value,
+) -> Result<(), sea_orm::error::DbErr> {
+    update_d_coin(db, user_id, d_coin, TransactionFlag::Up).await?;
+
+    Transaction::insert(transaction::ActiveModel {
+        d_coin: Set(d_coin),
+        message: Set(message),
+        flag: Set(TransactionFlag::Up),
+        metadata: Set(metadata),
+        owner_id: Set(user_id),
+        r#type: Set(TransactionType::Event),
+        ..Default::default()
+    })
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use crate::{
+    entities::{prelude::UserRating, user, user_rating},
+    models::{rating_change, RatingOnLadder, INITIAL_RATING},
+};
+use chrono::Utc;
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, Order, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, RelationTrait, Set,
+};
+
+pub async fn find_rating<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+    season: &str,
+) -> Result<i32, sea_orm::error::DbErr> {
+    let rating = UserRating::find_by_id((user_id, season.to_owned()))
+        .one(db)
+        .await?
+        .map_or(INITIAL_RATING, |user_rating| user_rating.rating);
+
+    Ok(rating)
+}
+
+/// Move both players' ratings after a settled match, `winner_id` is none on a draw.
+pub async fn update_ratings<C: ConnectionTrait>(
+    db: &C,
+    season: &str,
+    owner_id: i32,
+    opponent_id: i32,
+    winner_id: Option<i32>,
+) -> Result<(), sea_orm::error::DbErr> {
+    let owner_rating = find_rating(db, owner_id, season).await?;
+    let opponent_rating = find_rating(db, opponent_id, season).await?;
+
+    let owner_score = match winner_id {
+        Some(winner_id) if winner_id == owner_id => 1.0,
+        Some(_) => 0.0,
+        None => 0.5,
+    };
+
+    let ratings = [
+        (
+            owner_id,
+            owner_rating + rating_change(owner_rating, opponent_rating, owner_score),
+        ),
+        (
+            opponent_id,
+            opponent_rating + rating_change(opponent_rating, owner_rating, 1.0 - owner_score),
+        ),
+    ];
+
+    for (user_id, rating) in ratings {
+        UserRating::insert(user_rating::ActiveModel {
+            user_id: Set(user_id),
+            season: Set(season.to_owned()),
+            rating: Set(rating),
+            games: Set(1),
+            updated_at: Set(Utc::now().fixed_offset()),
+        })
+        .on_conflict(
+            OnConflict::columns([user_rating::Column::UserId, user_rating::Column::Season])
+                .update_columns([user_rating::Column::Rating, user_rating::Column::UpdatedAt])
+                .value(
+                    user_rating::Column::Games,
+                    Expr::col((UserRating, user_rating::Column::Games)).add(1),
+                )
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn find_ladder(
+    db: &DatabaseConnection,
+    season: &str,
+    page: u64,
+    take: u64,
+) -> Result<(Vec<RatingOnLadder>, u64), sea_orm::error::DbErr> {
+    let query_builder = UserRating::find()
+        .select_only()
+        .columns([
+            user_rating::Column::UserId,
+            user_rating::Column::Rating,
+            user_rating::Column::Games,
+        ])
+        .column(user::Column::FplId)
+        .column(user::Column::Name)
+        .join(
+            sea_orm::JoinType::InnerJoin,
+            user_rating::Relation::User.def(),
+        )
+        .filter(user_rating::Column::Season.eq(season));
+
+    let ratings = query_builder
+        .clone()
+        .order_by(user_rating::Column::Rating, Order::Desc)
+        .order_by(user_rating::Column::Games, Order::Desc)
+        .order_by(user_rating::Column::UserId, Order::Asc)
+        .offset((page - 1) * take)
+        .limit(take)
+        .into_model::<RatingOnLadder>()
+        .all(db)
+        .await?;
+
+    let total = query_builder.count(db).await?;
+
+    Ok((ratings, total))
+}
@@ -0,0 +1,115 @@
+use crate::entities::{
+    event_status,
+    prelude::{EventStatus, Match, Season},
+    r#match,
+    sea_orm_active_enums::MatchStatus,
+    season,
+};
+use chrono::Utc;
+use sea_orm::{
+    sea_query::OnConflict, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait,
+    Order, PaginatorTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+
+/// Keep `name` as the current season, archiving the previous one when FPL rolls over.
+///
+/// The rollover waits while the previous season still has `Next` or `Live` matches, since
+/// they are only settled or voided within their own season. Returns whether `name` is current.
+pub async fn update_current_season(
+    db: &DatabaseConnection,
+    name: String,
+    started_at: chrono::DateTime<chrono::FixedOffset>,
+) -> Result<bool, sea_orm::error::DbErr> {
+    let txn = db.begin().await?;
+
+    let previous_seasons = Season::find()
+        .filter(season::Column::IsCurrent.eq(true))
+        .filter(season::Column::Name.ne(name.clone()))
+        .all(&txn)
+        .await?;
+
+    for previous_season in previous_seasons {
+        if count_unfinished_matches(&txn, &previous_season.name).await? > 0 {
+            txn.rollback().await?;
+            return Ok(false);
+        }
+
+        archive_season(&txn, previous_season.name).await?;
+    }
+
+    Season::insert(season::ActiveModel {
+        name: Set(name),
+        started_at: Set(started_at),
+        is_current: Set(true),
+        archived_at: Set(None),
+    })
+    .on_conflict(
+        OnConflict::column(season::Column::Name)
+            .update_columns([season::Column::StartedAt, season::Column::IsCurrent])
+            .to_owned(),
+    )
+    .exec_without_returning(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(true)
+}
+
+pub async fn find_current_season(
+    db: &DatabaseConnection,
+) -> Result<Option<season::Model>, sea_orm::error::DbErr> {
+    Season::find()
+        .filter(season::Column::IsCurrent.eq(true))
+        .one(db)
+        .await
+}
+
+pub async fn find_all(
+    db: &DatabaseConnection,
+) -> Result<Vec<season::Model>, sea_orm::error::DbErr> {
+    Season::find()
+        .order_by(season::Column::StartedAt, Order::Desc)
+        .all(db)
+        .await
+}
+
+async fn count_unfinished_matches(
+    txn: &DatabaseTransaction,
+    season: &str,
+) -> Result<u64, sea_orm::error::DbErr> {
+    Match::find()
+        .filter(r#match::Column::Season.eq(season))
+        .filter(r#match::Column::Status.is_in([MatchStatus::Next, MatchStatus::Live]))
+        .count(txn)
+        .await
+}
+
+async fn archive_season(
+    txn: &DatabaseTransaction,
+    name: String,
+) -> Result<(), sea_orm::error::DbErr> {
+    Season::update_many()
+        .set(season::ActiveModel {
+            is_current: Set(false),
+            archived_at: Set(Some(Utc::now().fixed_offset())),
+            ..Default::default()
+        })
+        .filter(season::Column::Name.eq(name.clone()))
+        .exec(txn)
+        .await?;
+
+    // Gameweeks keep their deadlines for history but no longer point to the running season
+    EventStatus::update_many()
+        .set(event_status::ActiveModel {
+            is_previous: Set(false),
+            is_current: Set(false),
+            is_next: Set(false),
+            ..Default::default()
+        })
+        .filter(event_status::Column::Season.eq(name))
+        .exec(txn)
+        .await?;
+
+    Ok(())
+}
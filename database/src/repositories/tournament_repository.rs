@@ -0,0 +1,400 @@
+use crate::{
+    entities::{
+        prelude::{EventStatus, Match, Tournament, TournamentEntrant, Transaction, User},
+        r#match,
+        sea_orm_active_enums::{MatchStatus, TournamentStatus, TransactionFlag, TransactionType},
+        tournament, tournament_entrant, transaction,
+    },
+    models::TournamentActionError,
+    repositories::user_repository::{debit_d_coin, update_d_coin},
+};
+use chrono::Utc;
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait, Order,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+
+/// Share of the prize pool paid to the champion, the runner-up takes the rest.
+const CHAMPION_PRIZE_PERCENT: i32 = 70;
+
+pub async fn create_tournament(
+    db: &DatabaseConnection,
+    data: tournament::ActiveModel,
+) -> Result<tournament::Model, sea_orm::error::DbErr> {
+    Tournament::insert(data).exec_with_returning(db).await
+}
+
+pub async fn find_all_by_status(
+    db: &DatabaseConnection,
+    status: TournamentStatus,
+) -> Result<Vec<tournament::Model>, sea_orm::error::DbErr> {
+    Tournament::find()
+        .filter(tournament::Column::Status.eq(status))
+        .order_by(tournament::Column::Id, Order::Asc)
+        .all(db)
+        .await
+}
+
+/// Entrants ordered by registration, which is also their seeding.
+pub async fn find_entrants(
+    db: &DatabaseConnection,
+    tournament_id: i32,
+) -> Result<Vec<tournament_entrant::Model>, sea_orm::error::DbErr> {
+    TournamentEntrant::find()
+        .filter(tournament_entrant::Column::TournamentId.eq(tournament_id))
+        .order_by(tournament_entrant::Column::Id, Order::Asc)
+        .all(db)
+        .await
+}
+
+pub async fn find_round_matches(
+    db: &DatabaseConnection,
+    tournament_id: i32,
+    round: i32,
+) -> Result<Vec<r#match::Model>, sea_orm::error::DbErr> {
+    Match::find()
+        .filter(r#match::Column::TournamentId.eq(tournament_id))
+        .filter(r#match::Column::TournamentRound.eq(round))
+        .all(db)
+        .await
+}
+
+pub async fn register(
+    db: &DatabaseConnection,
+    tournament_id: i32,
+    user_id: i32,
+) -> Result<(), TournamentActionError> {
+    let txn = db.begin().await?;
+
+    // lock the tournament so entrants can not exceed the limit
+    let tournament = Tournament::find_by_id(tournament_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(TournamentActionError::TournamentNotFound)?;
+
+    if tournament.status != TournamentStatus::Registering {
+        return Err(TournamentActionError::RegistrationClosed);
+    }
+
+    let event = EventStatus::find_by_id((tournament.season.clone(), tournament.start_gameweek))
+        .one(&txn)
+        .await?;
+
+    if event.is_some_and(|event| event.deadline_time <= Utc::now()) {
+        return Err(TournamentActionError::RegistrationClosed);
+    }
+
+    let entrants = TournamentEntrant::find()
+        .filter(tournament_entrant::Column::TournamentId.eq(tournament_id))
+        .all(&txn)
+        .await?;
+
+    if entrants.iter().any(|entrant| entrant.user_id == user_id) {
+        return Err(TournamentActionError::AlreadyRegistered);
+    }
+
+    if entrants.len() as i32 >= tournament.max_entrants {
+        return Err(TournamentActionError::Full);
+    }
+
+    // lock the user so concurrent registrations can not spend the same d_coin
+    let user = User::find_by_id(user_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(TournamentActionError::UserNotFound)?;
+
+    if user.d_coin < tournament.entry_fee {
+        return Err(TournamentActionError::NotEnoughDCoin);
+    }
+
+    TournamentEntrant::insert(tournament_entrant::ActiveModel {
+        tournament_id: Set(tournament_id),
+        user_id: Set(user_id),
+        ..Default::default()
+    })
+    .exec_without_returning(&txn)
+    .await?;
+
+    Tournament::update_many()
+        .col_expr(
+            tournament::Column::PrizePool,
+            Expr::col(tournament::Column::PrizePool).add(tournament.entry_fee),
+        )
+        .filter(tournament::Column::Id.eq(tournament_id))
+        .exec(&txn)
+        .await?;
+
+    // collect entry fee
+    debit_d_coin(&txn, user_id, tournament.entry_fee).await?;
+
+    let metadata = serde_json::json!({
+        "tournament_id": tournament_id,
+    });
+    Transaction::insert(transaction::ActiveModel {
+        d_coin: Set(tournament.entry_fee),
+        message: Set(format!(
+            "You have registered for the tournament {}",
+            tournament.name
+        )),
+        flag: Set(TransactionFlag::Down),
+        metadata: Set(metadata),
+        owner_id: Set(user_id),
+        r#type: Set(TransactionType::TournamentEntry),
+        ..Default::default()
+    })
+    .exec_without_returning(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Create the matches of a round, eliminating the losers of the previous one.
+pub async fn start_round(
+    db: &DatabaseConnection,
+    tournament: &tournament::Model,
+    round: i32,
+    gameweek: i32,
+    pairs: Vec<(i32, i32)>,
+    eliminated_user_ids: Vec<i32>,
+) -> Result<(), sea_orm::error::DbErr> {
+    let txn = db.begin().await?;
+
+    // an overlapping run may have started the round already
+    let expected_status = match round {
+        1 => TournamentStatus::Registering,
+        _ => TournamentStatus::Running,
+    };
+    if lock_tournament(&txn, tournament.id, expected_status, round - 1)
+        .await?
+        .is_none()
+    {
+        return txn.rollback().await;
+    }
+
+    eliminate(&txn, tournament.id, round - 1, eliminated_user_ids).await?;
+
+    // the deadline has passed, so matches go live straight away
+    let matches: Vec<r#match::ActiveModel> = pairs
+        .into_iter()
+        .map(|(owner_id, opponent_id)| r#match::ActiveModel {
+            season: Set(tournament.season.clone()),
+            gameweek: Set(gameweek),
+            status: Set(MatchStatus::Live),
+            bet_amount: Set(0),
+            transfer_rule: Set(tournament.transfer_rule.clone()),
+            chip_rule: Set(tournament.chip_rule.clone()),
+            owner_id: Set(owner_id),
+            opponent_id: Set(Some(opponent_id)),
+            is_matched: Set(true),
+            matched_at: Set(Some(Utc::now().fixed_offset())),
+            is_private: Set(true),
+            tournament_id: Set(Some(tournament.id)),
+            tournament_round: Set(Some(round)),
+            ..Default::default()
+        })
+        .collect();
+
+    if !matches.is_empty() {
+        Match::insert_many(matches)
+            .exec_without_returning(&txn)
+            .await?;
+    }
+
+    Tournament::update_many()
+        .set(tournament::ActiveModel {
+            status: Set(TournamentStatus::Running),
+            current_round: Set(round),
+            ..Default::default()
+        })
+        .filter(tournament::Column::Id.eq(tournament.id))
+        .exec(&txn)
+        .await?;
+
+    txn.commit().await
+}
+
+pub async fn finish_tournament(
+    db: &DatabaseConnection,
+    tournament: &tournament::Model,
+    champion_id: i32,
+    runner_up_id: Option<i32>,
+    eliminated_user_ids: Vec<i32>,
+) -> Result<(), sea_orm::error::DbErr> {
+    let txn = db.begin().await?;
+
+    // never pay the prizes twice
+    if lock_tournament(
+        &txn,
+        tournament.id,
+        TournamentStatus::Running,
+        tournament.current_round,
+    )
+    .await?
+    .is_none()
+    {
+        return txn.rollback().await;
+    }
+
+    eliminate(
+        &txn,
+        tournament.id,
+        tournament.current_round,
+        eliminated_user_ids,
+    )
+    .await?;
+
+    Tournament::update_many()
+        .set(tournament::ActiveModel {
+            status: Set(TournamentStatus::Finished),
+            winner_id: Set(Some(champion_id)),
+            ..Default::default()
+        })
+        .filter(tournament::Column::Id.eq(tournament.id))
+        .exec(&txn)
+        .await?;
+
+    let runner_up_prize = match runner_up_id {
+        Some(_) => tournament.prize_pool * (100 - CHAMPION_PRIZE_PERCENT) / 100,
+        None => 0,
+    };
+    let champion_prize = tournament.prize_pool - runner_up_prize;
+
+    let prizes = [
+        (Some(champion_id), champion_prize, "won"),
+        (runner_up_id, runner_up_prize, "finished runner-up in"),
+    ];
+
+    for (user_id, prize, placement) in prizes {
+        let Some(user_id) = user_id.filter(|_| prize > 0) else {
+            continue;
+        };
+
+        update_d_coin(&txn, user_id, prize, TransactionFlag::Up).await?;
+
+        let metadata = serde_json::json!({
+            "tournament_id": tournament.id,
+        });
+        Transaction::insert(transaction::ActiveModel {
+            d_coin: Set(prize),
+            message: Set(format!(
+                "You have {} the tournament {}",
+                placement, tournament.name
+            )),
+            flag: Set(TransactionFlag::Up),
+            metadata: Set(metadata),
+            owner_id: Set(user_id),
+            r#type: Set(TransactionType::TournamentPrize),
+            ..Default::default()
+        })
+        .exec_without_returning(&txn)
+        .await?;
+    }
+
+    txn.commit().await
+}
+
+/// Refund every entrant of a tournament which could not start.
+pub async fn cancel_tournament(
+    db: &DatabaseConnection,
+    tournament: &tournament::Model,
+) -> Result<(), sea_orm::error::DbErr> {
+    let txn = db.begin().await?;
+
+    // never refund the entry fees twice
+    if lock_tournament(&txn, tournament.id, TournamentStatus::Registering, 0)
+        .await?
+        .is_none()
+    {
+        return txn.rollback().await;
+    }
+
+    Tournament::update_many()
+        .set(tournament::ActiveModel {
+            status: Set(TournamentStatus::Cancelled),
+            ..Default::default()
+        })
+        .filter(tournament::Column::Id.eq(tournament.id))
+        .filter(tournament::Column::Status.eq(TournamentStatus::Registering))
+        .exec(&txn)
+        .await?;
+
+    let entrants = TournamentEntrant::find()
+        .filter(tournament_entrant::Column::TournamentId.eq(tournament.id))
+        .all(&txn)
+        .await?;
+
+    for entrant in entrants.iter().filter(|_| tournament.entry_fee > 0) {
+        update_d_coin(
+            &txn,
+            entrant.user_id,
+            tournament.entry_fee,
+            TransactionFlag::Up,
+        )
+        .await?;
+
+        let metadata = serde_json::json!({
+            "tournament_id": tournament.id,
+        });
+        Transaction::insert(transaction::ActiveModel {
+            d_coin: Set(tournament.entry_fee),
+            message: Set(format!(
+                "The tournament {} has been cancelled, your entry fee has been refunded",
+                tournament.name
+            )),
+            flag: Set(TransactionFlag::Up),
+            metadata: Set(metadata),
+            owner_id: Set(entrant.user_id),
+            r#type: Set(TransactionType::Refund),
+            ..Default::default()
+        })
+        .exec_without_returning(&txn)
+        .await?;
+    }
+
+    txn.commit().await
+}
+
+async fn eliminate(
+    txn: &DatabaseTransaction,
+    tournament_id: i32,
+    round: i32,
+    user_ids: Vec<i32>,
+) -> Result<(), sea_orm::error::DbErr> {
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+
+    TournamentEntrant::update_many()
+        .set(tournament_entrant::ActiveModel {
+            eliminated_round: Set(Some(round)),
+            ..Default::default()
+        })
+        .filter(tournament_entrant::Column::TournamentId.eq(tournament_id))
+        .filter(tournament_entrant::Column::UserId.is_in(user_ids))
+        .exec(txn)
+        .await?;
+
+    Ok(())
+}
+
+/// Lock the tournament, only if it is still at `status` and `current_round`.
+async fn lock_tournament(
+    txn: &DatabaseTransaction,
+    tournament_id: i32,
+    status: TournamentStatus,
+    current_round: i32,
+) -> Result<Option<tournament::Model>, sea_orm::error::DbErr> {
+    Tournament::find_by_id(tournament_id)
+        .lock_exclusive()
+        .one(txn)
+        .await
+        .map(|tournament| {
+            tournament.filter(|tournament| {
+                tournament.status == status && tournament.current_round == current_round
+            })
+        })
+}
@@ -0,0 +1,168 @@
+use crate::{
+    entities::{
+        prelude::{Transaction, User},
+        sea_orm_active_enums::{TransactionFlag, TransactionType},
+        transaction, user,
+    },
+    models::{BalanceDiscrepancy, FindTransactionsParams, TransactionWithBalance},
+};
+use sea_orm::{
+    sea_query::{Alias, Asterisk, Expr, Query},
+    ActiveEnum, ConnectionTrait, DatabaseConnection, EntityTrait, FromQueryResult, Iterable, Order,
+    QuerySelect, Set, TransactionTrait,
+};
+
+/// Filters only narrow the rows returned, every row keeps its balance over the whole ledger.
+pub async fn find_transactions(
+    db: &DatabaseConnection,
+    FindTransactionsParams {
+        owner_id,
+        r#type,
+        flag,
+        from,
+        to,
+        before_id,
+        take,
+    }: FindTransactionsParams,
+) -> Result<Vec<TransactionWithBalance>, sea_orm::error::DbErr> {
+    let ledger = Alias::new("ledger");
+
+    let mut balances = Query::select();
+    balances
+        .columns(transaction::Column::iter())
+        .expr_as(
+            Expr::cust(
+                r#"(SUM(CASE WHEN "flag" = 'Up' THEN "d_coin" ELSE -"d_coin" END) OVER (ORDER BY "id"))::int8"#,
+            ),
+            Alias::new("balance"),
+        )
+        .from(Transaction)
+        .and_where(Expr::col(transaction::Column::OwnerId).eq(owner_id));
+
+    let mut query = Query::select();
+    query
+        .column(Asterisk)
+        .from_subquery(balances, ledger.clone())
+        .order_by((ledger.clone(), transaction::Column::Id), Order::Desc);
+
+    if let Some(r#type) = r#type {
+        query.and_where(
+            Expr::col((ledger.clone(), transaction::Column::Type))
+                .eq(Expr::val(r#type.to_value()).as_enum(TransactionType::name())),
+        );
+    }
+
+    if let Some(flag) = flag {
+        query.and_where(
+            Expr::col((ledger.clone(), transaction::Column::Flag))
+                .eq(Expr::val(flag.to_value()).as_enum(TransactionFlag::name())),
+        );
+    }
+
+    if let Some(from) = from {
+        query.and_where(Expr::col((ledger.clone(), transaction::Column::CreatedDate)).gte(from));
+    }
+
+    if let Some(to) = to {
+        query.and_where(Expr::col((ledger.clone(), transaction::Column::CreatedDate)).lt(to));
+    }
+
+    if let Some(before_id) = before_id {
+        query.and_where(Expr::col((ledger.clone(), transaction::Column::Id)).lt(before_id));
+    }
+
+    if let Some(take) = take {
+        query.limit(take);
+    }
+
+    TransactionWithBalance::find_by_statement(db.get_database_backend().build(&query))
+        .all(db)
+        .await
+}
+
+/// Users whose `d_coin` differs from the sum of their transactions.
+pub async fn find_balance_discrepancies<C: ConnectionTrait>(
+    db: &C,
+    user_id: Option<i32>,
+) -> Result<Vec<BalanceDiscrepancy>, sea_orm::error::DbErr> {
+    let ledger_balance = r#"COALESCE(SUM(CASE WHEN "transaction"."flag" = 'Up' THEN "transaction"."d_coin" ELSE -"transaction"."d_coin" END), 0)::int8"#;
+
+    let mut query = Query::select();
+    query
+        .expr_as(Expr::col((User, user::Column::Id)), Alias::new("user_id"))
+        .column((User, user::Column::DCoin))
+        .expr_as(Expr::cust(ledger_balance), Alias::new("ledger_balance"))
+        .from(User)
+        .left_join(
+            Transaction,
+            Expr::col((Transaction, transaction::Column::OwnerId)).equals((User, user::Column::Id)),
+        )
+        .group_by_col((User, user::Column::Id))
+        .and_having(Expr::col((User, user::Column::DCoin)).ne(Expr::cust(ledger_balance)))
+        .order_by((User, user::Column::Id), Order::Asc);
+
+    if let Some(user_id) = user_id {
+        query.and_where(Expr::col((User, user::Column::Id)).eq(user_id));
+    }
+
+    BalanceDiscrepancy::find_by_statement(db.get_database_backend().build(&query))
+        .all(db)
+        .await
+}
+
+/// Write an adjustment so the ledger adds up to the balance again, `d_coin` itself is left untouched.
+///
+/// The discrepancy is checked again under a lock, so a stale report never writes a wrong entry.
+/// `corrected_by` is the admin asking for it, none when the scheduler does.
+pub async fn correct_balance(
+    db: &DatabaseConnection,
+    user_id: i32,
+    corrected_by: Option<i32>,
+) -> Result<Option<BalanceDiscrepancy>, sea_orm::error::DbErr> {
+    let txn = db.begin().await?;
+
+    // lock the user so no transaction lands while the ledger is summed
+    if User::find_by_id(user_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .is_none()
+    {
+        txn.rollback().await?;
+        return Ok(None);
+    }
+
+    let discrepancy = find_balance_discrepancies(&txn, Some(user_id)).await?.pop();
+
+    let Some(discrepancy) = discrepancy else {
+        txn.rollback().await?;
+        return Ok(None);
+    };
+
+    let difference = discrepancy.difference();
+
+    Transaction::insert(transaction::ActiveModel {
+        d_coin: Set(difference.unsigned_abs() as i32),
+        message: Set("Your balance history has been adjusted".to_owned()),
+        flag: Set(if difference > 0 {
+            TransactionFlag::Up
+        } else {
+            TransactionFlag::Down
+        }),
+        metadata: Set(serde_json::json!({
+            "reason": "reconciliation",
+            "d_coin": discrepancy.d_coin,
+            "ledger_balance": discrepancy.ledger_balance,
+            "corrected_by": corrected_by,
+        })),
+        owner_id: Set(user_id),
+        r#type: Set(TransactionType::Adjustment),
+        ..Default::default()
+    })
+    .exec_without_returning(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(Some(discrepancy))
+}
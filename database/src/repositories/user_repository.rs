@@ -1,7 +1,14 @@
-use crate::entities::{prelude::User, sea_orm_active_enums::TransactionFlag, user};
+use crate::{
+    entities::{
+        prelude::{User, UserIdentity},
+        sea_orm_active_enums::TransactionFlag,
+        user, user_identity,
+    },
+    models::DebitError,
+};
 use sea_orm::{
-    sea_query::{Expr, Query},
-    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryTrait, Set,
+    sea_query::{Expr, OnConflict, Query},
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
 };
 
 pub async fn find_by_id(
@@ -11,7 +18,27 @@ pub async fn find_by_id(
     User::find().filter(user::Column::Id.eq(id)).one(db).await
 }
 
-pub async fn save<'r>(
+pub async fn find_by_ids(
+    db: &DatabaseConnection,
+    ids: Vec<i32>,
+) -> Result<Vec<user::Model>, sea_orm::error::DbErr> {
+    User::find()
+        .filter(user::Column::Id.is_in(ids))
+        .all(db)
+        .await
+}
+
+pub async fn find_by_fpl_id(
+    db: &DatabaseConnection,
+    fpl_id: i32,
+) -> Result<Option<user::Model>, sea_orm::error::DbErr> {
+    User::find()
+        .filter(user::Column::FplId.eq(fpl_id))
+        .one(db)
+        .await
+}
+
+pub async fn save(
     db: &DatabaseConnection,
     data: user::ActiveModel,
 ) -> Result<user::Model, sea_orm::error::DbErr> {
@@ -20,22 +47,57 @@ pub async fn save<'r>(
     Ok(new_user)
 }
 
-pub async fn find_first_by_platform_id<S: Into<String>>(
+/// Users registered before `user_identity` existed only have their Google or Facebook id
+/// on the user row, their identity is linked the first time they are found by it.
+pub async fn find_by_identity(
     db: &DatabaseConnection,
-    google_id: Option<S>,
-    facebook_id: Option<S>,
+    provider: &str,
+    subject: &str,
 ) -> Result<Option<user::Model>, sea_orm::error::DbErr> {
     let user = User::find()
-        .apply_if(google_id, |query, google_id| {
-            query.filter(user::Column::GoogleId.eq(google_id.into()))
-        })
-        .apply_if(facebook_id, |query, facebook_id| {
-            query.filter(user::Column::GoogleId.eq(facebook_id.into()))
-        })
+        .inner_join(UserIdentity)
+        .filter(user_identity::Column::Provider.eq(provider))
+        .filter(user_identity::Column::Subject.eq(subject))
         .one(db)
         .await?;
 
-    Ok(user)
+    if user.is_some() {
+        return Ok(user);
+    }
+
+    let legacy_column = match provider {
+        "google" => user::Column::GoogleId,
+        "facebook" => user::Column::FacebookId,
+        _ => return Ok(None),
+    };
+
+    let Some(user) = User::find()
+        .filter(legacy_column.eq(subject))
+        .one(db)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    UserIdentity::insert(user_identity::ActiveModel {
+        provider: Set(provider.to_owned()),
+        subject: Set(subject.to_owned()),
+        email: Set(Some(user.email.clone())),
+        user_id: Set(user.id),
+        ..Default::default()
+    })
+    .on_conflict(
+        OnConflict::columns([
+            user_identity::Column::Provider,
+            user_identity::Column::Subject,
+        ])
+        .do_nothing()
+        .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(Some(user))
 }
 
 pub async fn update_fpl_id(
@@ -53,8 +115,19 @@ pub async fn update_fpl_id(
     .map(|_| ())
 }
 
-pub async fn update_d_coin(
-    db: &DatabaseConnection,
+/// Like `update_d_coin` going down, an overdrawing debit is told apart from other failures.
+pub async fn debit_d_coin<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+    d_coin: i32,
+) -> Result<(), DebitError> {
+    update_d_coin(db, user_id, d_coin, TransactionFlag::Down)
+        .await
+        .map_err(DebitError::from)
+}
+
+pub async fn update_d_coin<C: ConnectionTrait>(
+    db: &C,
     user_id: i32,
     d_coin: i32,
     kind: TransactionFlag,
@@ -72,7 +145,16 @@ pub async fn update_d_coin(
         )
         .and_where(user::Column::Id.eq(user_id));
 
-    db.execute(db.get_database_backend().build(&query)).await?;
+    // never let a debit overdraw the balance, even if a caller skipped its own check
+    if kind == TransactionFlag::Down {
+        query.and_where(user::Column::DCoin.gte(d_coin));
+    }
+
+    let result = db.execute(db.get_database_backend().build(&query)).await?;
+
+    if result.rows_affected() == 0 {
+        return Err(sea_orm::error::DbErr::RecordNotUpdated);
+    }
 
     Ok(())
 }
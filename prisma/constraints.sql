@@ -0,0 +1,4 @@
+-- Constraints prisma can not express in schema.prisma, apply them after `prisma db push`.
+
+ALTER TABLE "user" DROP CONSTRAINT IF EXISTS "user_d_coin_non_negative";
+ALTER TABLE "user" ADD CONSTRAINT "user_d_coin_non_negative" CHECK ("d_coin" >= 0);
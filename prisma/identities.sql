@@ -0,0 +1,9 @@
+-- Copy the sign in ids of users registered before user_identity existed, apply it after `prisma db push`.
+
+INSERT INTO "user_identity" ("provider", "subject", "email", "user_id")
+SELECT 'google', "google_id", "email", "id" FROM "user" WHERE "google_id" IS NOT NULL
+ON CONFLICT DO NOTHING;
+
+INSERT INTO "user_identity" ("provider", "subject", "email", "user_id")
+SELECT 'facebook', "facebook_id", "email", "id" FROM "user" WHERE "facebook_id" IS NOT NULL
+ON CONFLICT DO NOTHING;
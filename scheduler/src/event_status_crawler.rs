@@ -1,13 +1,53 @@
-use database::{repositories::event_status_repository, sea_orm::DatabaseConnection};
+use database::{
+    repositories::{event_status_repository, season_repository},
+    sea_orm::DatabaseConnection,
+};
 use services::fantasy::bootstrap;
-use std::error::Error;
+use std::{error::Error, sync::Mutex};
+
+/// The season last reported as waiting, so a hold is logged once rather than on every tick.
+static WAITING_SEASON: Mutex<Option<String>> = Mutex::new(None);
 
 pub async fn update_event_status_every_3_mins(
     db: &DatabaseConnection,
 ) -> Result<(), Box<dyn Error>> {
     let bootstrap = bootstrap::get_bootstrap().await?;
 
-    let _ = event_status_repository::update_events(db, bootstrap.events).await?;
+    // Gameweeks are keyed by season, they can not be stored without it
+    let Some((season, first_event)) = bootstrap.season() else {
+        return Ok(());
+    };
+
+    let started_at = chrono::DateTime::parse_from_rfc3339(&first_event.deadline_time)?;
+
+    // Gameweeks of the new season wait until the previous one has no match left to settle
+    let is_current =
+        season_repository::update_current_season(db, season.clone(), started_at).await?;
+
+    report_waiting(&season, !is_current);
+
+    if !is_current {
+        return Ok(());
+    }
+
+    event_status_repository::update_events(db, &season, bootstrap.events).await?;
 
     Ok(())
 }
+
+fn report_waiting(season: &str, is_waiting: bool) {
+    let mut waiting_season = WAITING_SEASON.lock().unwrap();
+
+    if !is_waiting {
+        *waiting_season = None;
+        return;
+    }
+
+    if waiting_season.as_deref() != Some(season) {
+        eprintln!(
+            "Season {} waits for the previous season to finish its matches",
+            season
+        );
+        *waiting_season = Some(season.to_owned());
+    }
+}
@@ -1,6 +1,7 @@
 use chrono::Utc;
 use cron::Schedule;
 use std::error::Error;
+use std::fmt::Display;
 use std::future::Future;
 use std::pin::Pin;
 use std::str::FromStr;
@@ -9,27 +10,37 @@ use tokio::time::sleep;
 pub enum CronExpression {
     EveryFiveMinutes,
     EveryThreeMinutes,
+    /// On every fifth minute of the clock, for jobs that call external APIs.
+    EveryFiveMinutesSharp,
+    EveryHour,
 }
 
-impl ToString for CronExpression {
-    fn to_string(&self) -> String {
+impl Display for CronExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use CronExpression::*;
         match self {
-            EveryFiveMinutes => "1/10 * * * * *".into(),
-            EveryThreeMinutes => "1/5 * * * * *".into(),
+            EveryFiveMinutes => write!(f, "1/10 * * * * *"),
+            EveryThreeMinutes => write!(f, "1/5 * * * * *"),
+            EveryFiveMinutesSharp => write!(f, "0 */5 * * * *"),
+            EveryHour => write!(f, "0 0 * * * *"),
         }
     }
 }
 
+type Job<C> = &'static dyn Fn(C) -> Pin<Box<dyn Future<Output = ()>>>;
+
 pub struct Scheduler<C: 'static + Sized + Default + Clone> {
     context: Option<C>,
-    jobs: Vec<(
-        String,
-        &'static dyn Fn(C) -> Pin<Box<dyn Future<Output = ()>>>,
-    )>,
+    jobs: Vec<(String, Job<C>)>,
+}
+
+impl<C: 'static + Default + Clone> Default for Scheduler<C> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl<'a, C: 'static + Default + Clone> Scheduler<C> {
+impl<C: 'static + Default + Clone> Scheduler<C> {
     pub fn new() -> Self {
         Self {
             context: None,
@@ -0,0 +1,127 @@
+use database::{
+    entities::r#match,
+    repositories::{event_status_repository, match_repository, user_repository},
+    sea_orm::DatabaseConnection,
+};
+use services::fantasy::{
+    history::{self, History},
+    live, picks,
+};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    error::Error,
+};
+
+pub async fn update_live_points(db: &DatabaseConnection) -> Result<(), Box<dyn Error>> {
+    let current_event = event_status_repository::find_current_event(db).await?;
+
+    let Some(current_event) = current_event.filter(|event| !event.finished) else {
+        return Ok(());
+    };
+
+    let matches = match_repository::find_all_matched_live_by_gameweek(
+        db,
+        &current_event.season,
+        current_event.gameweek,
+    )
+    .await?;
+
+    if matches.is_empty() {
+        return Ok(());
+    }
+
+    let points_by_element = live::get_live_event(current_event.gameweek)
+        .await?
+        .points_by_element();
+
+    let participant_ids = matches
+        .iter()
+        .flat_map(|r#match| [Some(r#match.owner_id), r#match.opponent_id])
+        .flatten()
+        .collect();
+
+    let fpl_ids: HashMap<i32, Option<i32>> = user_repository::find_by_ids(db, participant_ids)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.fpl_id))
+        .collect();
+
+    let mut scores = Scores {
+        current_gameweek: current_event.gameweek,
+        points_by_element,
+        points: HashMap::new(),
+        histories: HashMap::new(),
+    };
+
+    for r#match in matches {
+        update_match_points(db, &r#match, &fpl_ids, &mut scores)
+            .await
+            .unwrap_or_else(|err| {
+                eprintln!(
+                    "An error occured when update live points of match {}: {}",
+                    r#match.id, err
+                );
+            });
+    }
+
+    Ok(())
+}
+
+/// Points fetched once per entry, shared by every match it plays.
+struct Scores {
+    current_gameweek: i32,
+    points_by_element: HashMap<i32, i32>,
+    points: HashMap<i32, i32>,
+    histories: HashMap<i32, History>,
+}
+
+async fn update_match_points(
+    db: &DatabaseConnection,
+    r#match: &r#match::Model,
+    fpl_ids: &HashMap<i32, Option<i32>>,
+    scores: &mut Scores,
+) -> Result<(), Box<dyn Error>> {
+    let Some(opponent_id) = r#match.opponent_id else {
+        return Ok(());
+    };
+
+    let owner_point = get_provisional_point(r#match, fpl_ids, scores, r#match.owner_id).await?;
+    let opponent_point = get_provisional_point(r#match, fpl_ids, scores, opponent_id).await?;
+
+    match_repository::update_live_points(db, r#match.id, owner_point, opponent_point).await?;
+
+    Ok(())
+}
+
+/// The current gameweek scores provisionally, earlier gameweeks of the match
+/// add their final points, a participant without fpl_id can not score, so the point is 0.
+async fn get_provisional_point(
+    r#match: &r#match::Model,
+    fpl_ids: &HashMap<i32, Option<i32>>,
+    scores: &mut Scores,
+    user_id: i32,
+) -> Result<i32, Box<dyn Error>> {
+    let Some(fpl_id) = fpl_ids.get(&user_id).copied().flatten() else {
+        return Ok(0);
+    };
+
+    let current_point = match scores.points.entry(fpl_id) {
+        Entry::Occupied(entry) => *entry.get(),
+        Entry::Vacant(entry) => *entry.insert(
+            picks::get_entry_picks(fpl_id, scores.current_gameweek)
+                .await?
+                .provisional_points(&scores.points_by_element),
+        ),
+    };
+
+    if r#match.gameweek == scores.current_gameweek {
+        return Ok(current_point);
+    }
+
+    let history = match scores.histories.entry(fpl_id) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(history::get_history(fpl_id).await?),
+    };
+
+    Ok(history.net_points_between(r#match.gameweek, scores.current_gameweek - 1) + current_point)
+}
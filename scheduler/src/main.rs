@@ -1,5 +1,10 @@
 mod event_status_crawler;
+mod live_scoring;
 mod match_worker;
+mod reconciliation;
+mod rule_checker;
+mod settlement;
+mod tournament_worker;
 
 use database::sea_orm::{ConnectOptions, Database};
 use dotenv::dotenv;
@@ -25,6 +30,15 @@ async fn main() {
                     });
             })
         })
+        .add_job(CronExpression::EveryFiveMinutes, &|db| {
+            Box::pin(async move {
+                match_worker::void_unmatched_matches(&db)
+                    .await
+                    .unwrap_or_else(|err| {
+                        eprintln!("An error occured when void unmatched matches: {}", err);
+                    });
+            })
+        })
         .add_job(CronExpression::EveryFiveMinutes, &|db| {
             Box::pin(async move {
                 match_worker::update_matches_to_live(&db)
@@ -43,6 +57,33 @@ async fn main() {
                     });
             })
         })
+        .add_job(CronExpression::EveryFiveMinutesSharp, &|db| {
+            Box::pin(async move {
+                live_scoring::update_live_points(&db)
+                    .await
+                    .unwrap_or_else(|err| {
+                        eprintln!("An error occured when update live points: {}", err);
+                    });
+            })
+        })
+        .add_job(CronExpression::EveryFiveMinutes, &|db| {
+            Box::pin(async move {
+                tournament_worker::run_tournaments(&db)
+                    .await
+                    .unwrap_or_else(|err| {
+                        eprintln!("An error occured when run tournaments: {}", err);
+                    });
+            })
+        })
+        .add_job(CronExpression::EveryHour, &|db| {
+            Box::pin(async move {
+                reconciliation::reconcile_balances(&db)
+                    .await
+                    .unwrap_or_else(|err| {
+                        eprintln!("An error occured when reconcile balances: {}", err);
+                    });
+            })
+        })
         .start()
         .await
         .unwrap_or_else(|err| {
@@ -5,6 +5,8 @@ use database::{
     sea_orm::DatabaseConnection,
 };
 
+use crate::settlement;
+
 pub async fn update_matches_to_live(db: &DatabaseConnection) -> Result<(), Box<dyn Error>> {
     let current_event = event_status_repository::find_current_event(db).await?;
 
@@ -12,21 +14,39 @@ pub async fn update_matches_to_live(db: &DatabaseConnection) -> Result<(), Box<d
         return Ok(());
     };
 
-    let _ = match_repository::update_all_next_round_to_live_by_gameweek(db, current_event.gameweek)
-        .await?;
+    match_repository::update_all_next_round_to_live_by_gameweek(
+        db,
+        &current_event.season,
+        current_event.gameweek,
+    )
+    .await?;
 
     Ok(())
 }
 
 pub async fn update_matches_to_finished(db: &DatabaseConnection) -> Result<(), Box<dyn Error>> {
-    let previous_event = event_status_repository::find_finished_previous_event(db).await?;
+    for event in event_status_repository::find_settleable_events(db).await? {
+        settle_gameweek(db, &event.season, event.gameweek).await?;
+    }
 
-    let Some(previous_event) = previous_event else {
-        return Ok(());
-    };
+    Ok(())
+}
 
-    let _ = match_repository::update_all_live_to_finished_by_gameweek(db, previous_event.gameweek)
+pub async fn settle_gameweek(
+    db: &DatabaseConnection,
+    season: &str,
+    gameweek: i32,
+) -> Result<(), Box<dyn Error>> {
+    settlement::settle_matches_by_gameweek(db, season, gameweek).await?;
+
+    match_repository::update_all_unmatched_live_to_finished_by_gameweek(db, season, gameweek)
         .await?;
 
     Ok(())
 }
+
+pub async fn void_unmatched_matches(db: &DatabaseConnection) -> Result<(), Box<dyn Error>> {
+    match_repository::void_unmatched_matches_past_deadline(db).await?;
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use database::{repositories::transaction_repository, sea_orm::DatabaseConnection};
+use std::error::Error;
+
+/// Report the users whose balance drifted from their ledger,
+/// adjustments are only written when `RECONCILIATION_AUTO_CORRECT` is set to `true`.
+pub async fn reconcile_balances(db: &DatabaseConnection) -> Result<(), Box<dyn Error>> {
+    let auto_correct =
+        std::env::var("RECONCILIATION_AUTO_CORRECT").is_ok_and(|value| value == "true");
+
+    for discrepancy in transaction_repository::find_balance_discrepancies(db, None).await? {
+        eprintln!(
+            "Balance of user {} is {} but its ledger sums to {}",
+            discrepancy.user_id, discrepancy.d_coin, discrepancy.ledger_balance
+        );
+
+        if auto_correct {
+            transaction_repository::correct_balance(db, discrepancy.user_id, None)
+                .await
+                .unwrap_or_else(|err| {
+                    eprintln!(
+                        "An error occured when correct balance of user {}: {}",
+                        discrepancy.user_id, err
+                    );
+                    None
+                });
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use database::entities::sea_orm_active_enums::{ChipRule, OffendedRule, TransferRule};
+use services::fantasy::history::History;
+
+const SQUAD_CHIPS: [&str; 2] = ["wildcard", "freehit"];
+
+/// Find the first rule an entry broke over the gameweeks from `from` to `to`.
+pub fn find_offended_rule(
+    transfer_rule: &TransferRule,
+    chip_rule: &ChipRule,
+    history: &History,
+    from: i32,
+    to: i32,
+) -> Option<OffendedRule> {
+    (from..=to).find_map(|gameweek| {
+        let event_transfers = history
+            .event(gameweek)
+            .map_or(0, |event| event.event_transfers);
+
+        find_offended_rule_on_gameweek(
+            transfer_rule,
+            chip_rule,
+            event_transfers,
+            history.chip(gameweek),
+        )
+    })
+}
+
+/// Find the rule an entry broke on a gameweek.
+///
+/// `ChipRule::NoChip` forbids every chip, `ChipRule::AnyChip` allows playing a chip
+/// but still counts transfers made under wildcard or free hit, `ChipRule::All` allows
+/// every chip with its full effect, so those transfers are free from the transfer rule.
+fn find_offended_rule_on_gameweek(
+    transfer_rule: &TransferRule,
+    chip_rule: &ChipRule,
+    event_transfers: i32,
+    active_chip: Option<&str>,
+) -> Option<OffendedRule> {
+    if *chip_rule == ChipRule::NoChip && active_chip.is_some() {
+        return Some(OffendedRule::UsingChip);
+    }
+
+    let is_free_transferring =
+        *chip_rule == ChipRule::All && active_chip.is_some_and(|chip| SQUAD_CHIPS.contains(&chip));
+
+    let transfer_limit = match transfer_rule {
+        TransferRule::Limit0 => 0,
+        TransferRule::Limit1 => 1,
+        TransferRule::Limit2 => 2,
+        TransferRule::Limit3 => 3,
+        TransferRule::Limit4 => 4,
+        TransferRule::NoLimit => return None,
+    };
+
+    if !is_free_transferring && event_transfers > transfer_limit {
+        return Some(OffendedRule::Transferring);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use services::fantasy::{history::ChipPlay, picks::EntryHistory};
+
+    /// `gameweeks` are (event, event_transfers), `chips` are (event, name).
+    fn history(gameweeks: &[(i32, i32)], chips: &[(i32, &str)]) -> History {
+        History {
+            current: gameweeks
+                .iter()
+                .map(|&(event, event_transfers)| EntryHistory {
+                    event,
+                    points: 50,
+                    total_points: 50 * event,
+                    rank: None,
+                    overall_rank: None,
+                    bank: 0,
+                    value: 1000,
+                    event_transfers,
+                    event_transfers_cost: 0,
+                    points_on_bench: 0,
+                })
+                .collect(),
+            chips: chips
+                .iter()
+                .map(|&(event, name)| ChipPlay {
+                    name: name.to_owned(),
+                    time: String::new(),
+                    event,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn transfers_within_limit_are_fine() {
+        let history = history(&[(10, 1)], &[]);
+
+        assert_eq!(
+            find_offended_rule(&TransferRule::Limit1, &ChipRule::All, &history, 10, 10),
+            None
+        );
+    }
+
+    #[test]
+    fn transfers_over_limit_offend() {
+        let history = history(&[(10, 2)], &[]);
+
+        assert_eq!(
+            find_offended_rule(&TransferRule::Limit1, &ChipRule::All, &history, 10, 10),
+            Some(OffendedRule::Transferring)
+        );
+        assert_eq!(
+            find_offended_rule(&TransferRule::NoLimit, &ChipRule::All, &history, 10, 10),
+            None
+        );
+    }
+
+    #[test]
+    fn any_chip_is_forbidden_by_no_chip() {
+        let history = history(&[(10, 0)], &[(10, "bboost")]);
+
+        assert_eq!(
+            find_offended_rule(&TransferRule::NoLimit, &ChipRule::NoChip, &history, 10, 10),
+            Some(OffendedRule::UsingChip)
+        );
+    }
+
+    #[test]
+    fn wildcard_transfers_count_only_under_any_chip() {
+        let history = history(&[(10, 8)], &[(10, "wildcard")]);
+
+        assert_eq!(
+            find_offended_rule(&TransferRule::Limit1, &ChipRule::AnyChip, &history, 10, 10),
+            Some(OffendedRule::Transferring)
+        );
+        assert_eq!(
+            find_offended_rule(&TransferRule::Limit1, &ChipRule::All, &history, 10, 10),
+            None
+        );
+    }
+
+    #[test]
+    fn every_gameweek_of_the_range_is_checked() {
+        let history = history(&[(10, 0), (11, 0), (12, 3)], &[]);
+
+        assert_eq!(
+            find_offended_rule(&TransferRule::Limit0, &ChipRule::All, &history, 10, 11),
+            None
+        );
+        assert_eq!(
+            find_offended_rule(&TransferRule::Limit0, &ChipRule::All, &history, 10, 12),
+            Some(OffendedRule::Transferring)
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use crate::rule_checker;
+use chrono::{DateTime, Duration, Utc};
+use database::{
+    entities::{r#match, sea_orm_active_enums::OffendedRule},
+    models::MatchSettlement,
+    repositories::{match_repository, user_repository},
+    sea_orm::DatabaseConnection,
+};
+use services::fantasy::history::{self, History};
+use std::{
+    collections::{hash_map::Entry, BTreeMap, HashMap},
+    error::Error,
+    sync::Mutex,
+};
+
+/// Minutes before the first retry of a match that failed to settle, doubled on every failure.
+const FIRST_RETRY_MINUTES: i64 = 1;
+const MAX_RETRY_MINUTES: i64 = 6 * 60;
+
+static RETRIES: Mutex<SettlementRetries> = Mutex::new(SettlementRetries::new());
+
+struct Failure {
+    attempts: u32,
+    retry_at: DateTime<Utc>,
+}
+
+/// Matches that failed to settle, so one bad entry does not hit the FPL API on every tick.
+struct SettlementRetries {
+    failures: BTreeMap<i32, Failure>,
+}
+
+impl SettlementRetries {
+    const fn new() -> Self {
+        Self {
+            failures: BTreeMap::new(),
+        }
+    }
+
+    fn is_due(&self, match_id: i32, now: DateTime<Utc>) -> bool {
+        self.failures
+            .get(&match_id)
+            .is_none_or(|failure| failure.retry_at <= now)
+    }
+
+    /// Returns the failed attempts so far and when the match is tried again.
+    fn record_failure(&mut self, match_id: i32, now: DateTime<Utc>) -> (u32, DateTime<Utc>) {
+        let failure = self.failures.entry(match_id).or_insert(Failure {
+            attempts: 0,
+            retry_at: now,
+        });
+
+        failure.attempts += 1;
+        failure.retry_at = now + retry_delay(failure.attempts);
+
+        (failure.attempts, failure.retry_at)
+    }
+
+    fn record_success(&mut self, match_id: i32) {
+        self.failures.remove(&match_id);
+    }
+}
+
+/// Capped rather than given up, the match stays Live until it settles.
+fn retry_delay(attempts: u32) -> Duration {
+    let factor = 2_i64.saturating_pow(attempts.saturating_sub(1));
+
+    Duration::minutes(
+        FIRST_RETRY_MINUTES
+            .saturating_mul(factor)
+            .min(MAX_RETRY_MINUTES),
+    )
+}
+
+pub async fn settle_matches_by_gameweek(
+    db: &DatabaseConnection,
+    season: &str,
+    gameweek: i32,
+) -> Result<(), Box<dyn Error>> {
+    let now = Utc::now();
+    let matches: Vec<_> =
+        match_repository::find_all_matched_live_ending_at_gameweek(db, season, gameweek)
+            .await?
+            .into_iter()
+            .filter(|r#match| RETRIES.lock().unwrap().is_due(r#match.id, now))
+            .collect();
+
+    if matches.is_empty() {
+        return Ok(());
+    }
+
+    let participant_ids = matches
+        .iter()
+        .flat_map(|r#match| [Some(r#match.owner_id), r#match.opponent_id])
+        .flatten()
+        .collect();
+
+    let fpl_ids: HashMap<i32, Option<i32>> = user_repository::find_by_ids(db, participant_ids)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.fpl_id))
+        .collect();
+
+    // Same entry can play many matches, fetch its history once
+    let mut histories: HashMap<i32, History> = HashMap::new();
+
+    for r#match in matches {
+        match settle_match(db, &r#match, &fpl_ids, &mut histories).await {
+            Ok(()) => RETRIES.lock().unwrap().record_success(r#match.id),
+            Err(err) => {
+                let (attempts, retry_at) = RETRIES.lock().unwrap().record_failure(r#match.id, now);
+
+                eprintln!(
+                    "An error occured when settle match {} (attempt {}, retry at {}): {}",
+                    r#match.id, attempts, retry_at, err
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn settle_match(
+    db: &DatabaseConnection,
+    r#match: &r#match::Model,
+    fpl_ids: &HashMap<i32, Option<i32>>,
+    histories: &mut HashMap<i32, History>,
+) -> Result<(), Box<dyn Error>> {
+    let Some(opponent_id) = r#match.opponent_id else {
+        return Ok(());
+    };
+
+    let (owner_point, owner_offended_rule) =
+        get_result(r#match, fpl_ids, histories, r#match.owner_id).await?;
+    let (opponent_point, opponent_offended_rule) =
+        get_result(r#match, fpl_ids, histories, opponent_id).await?;
+
+    match_repository::settle_match(
+        db,
+        r#match.id,
+        MatchSettlement {
+            owner_point,
+            opponent_point,
+            owner_offended_rule,
+            opponent_offended_rule,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Points are summed over every gameweek of the match,
+/// a participant without fpl_id can not score, so they forfeit the match.
+async fn get_result(
+    r#match: &r#match::Model,
+    fpl_ids: &HashMap<i32, Option<i32>>,
+    histories: &mut HashMap<i32, History>,
+    user_id: i32,
+) -> Result<(i32, Option<OffendedRule>), Box<dyn Error>> {
+    let Some(fpl_id) = fpl_ids.get(&user_id).copied().flatten() else {
+        return Ok((0, Some(OffendedRule::MissingEntry)));
+    };
+
+    let history = match histories.entry(fpl_id) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(history::get_history(fpl_id).await?),
+    };
+
+    let (from, to) = (
+        r#match.gameweek,
+        r#match.end_gameweek.unwrap_or(r#match.gameweek),
+    );
+
+    let offended_rule = rule_checker::find_offended_rule(
+        &r#match.transfer_rule,
+        &r#match.chip_rule,
+        history,
+        from,
+        to,
+    );
+
+    Ok((history.net_points_between(from, to), offended_rule))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_match_waits_for_its_retry() {
+        let now = Utc::now();
+        let mut retries = SettlementRetries::new();
+
+        assert!(retries.is_due(1, now));
+
+        let (attempts, retry_at) = retries.record_failure(1, now);
+
+        assert_eq!(attempts, 1);
+        assert_eq!(retry_at, now + Duration::minutes(FIRST_RETRY_MINUTES));
+        assert!(!retries.is_due(1, now));
+        assert!(retries.is_due(1, retry_at));
+        assert!(retries.is_due(2, now));
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap() {
+        assert_eq!(retry_delay(1), Duration::minutes(1));
+        assert_eq!(retry_delay(2), Duration::minutes(2));
+        assert_eq!(retry_delay(4), Duration::minutes(8));
+        assert_eq!(retry_delay(20), Duration::minutes(MAX_RETRY_MINUTES));
+        assert_eq!(retry_delay(u32::MAX), Duration::minutes(MAX_RETRY_MINUTES));
+    }
+
+    #[test]
+    fn settled_match_is_forgotten() {
+        let now = Utc::now();
+        let mut retries = SettlementRetries::new();
+
+        retries.record_failure(1, now);
+        retries.record_success(1);
+
+        assert!(retries.is_due(1, now));
+        assert_eq!(retries.record_failure(1, now).0, 1);
+    }
+}
@@ -0,0 +1,309 @@
+use crate::match_worker;
+use chrono::{DateTime, Utc};
+use database::{
+    entities::{
+        event_status,
+        sea_orm_active_enums::{MatchStatus, TournamentStatus},
+        tournament, tournament_entrant,
+    },
+    repositories::{event_status_repository, tournament_repository},
+    sea_orm::DatabaseConnection,
+};
+use std::{collections::HashMap, error::Error};
+
+#[derive(Debug, PartialEq)]
+enum RoundStart {
+    /// The deadline of its gameweek has not passed yet.
+    NotYet,
+    Open,
+    /// Its gameweek is checked and no longer previous or current, the match worker will not
+    /// settle it, so the round is settled as soon as it starts.
+    Over,
+}
+
+pub async fn run_tournaments(db: &DatabaseConnection) -> Result<(), Box<dyn Error>> {
+    for tournament in
+        tournament_repository::find_all_by_status(db, TournamentStatus::Registering).await?
+    {
+        close_registration(db, &tournament)
+            .await
+            .unwrap_or_else(|err| {
+                eprintln!(
+                    "An error occured when start tournament {}: {}",
+                    tournament.id, err
+                );
+            });
+    }
+
+    for tournament in
+        tournament_repository::find_all_by_status(db, TournamentStatus::Running).await?
+    {
+        advance(db, &tournament).await.unwrap_or_else(|err| {
+            eprintln!(
+                "An error occured when advance tournament {}: {}",
+                tournament.id, err
+            );
+        });
+    }
+
+    Ok(())
+}
+
+async fn close_registration(
+    db: &DatabaseConnection,
+    tournament: &tournament::Model,
+) -> Result<(), Box<dyn Error>> {
+    let round_start = find_round_start(db, tournament, tournament.start_gameweek).await?;
+
+    if round_start == RoundStart::NotYet {
+        return Ok(());
+    }
+
+    let entrants = tournament_repository::find_entrants(db, tournament.id).await?;
+
+    if entrants.len() < 2 {
+        tournament_repository::cancel_tournament(db, tournament).await?;
+        return Ok(());
+    }
+
+    let user_ids = entrants.iter().map(|entrant| entrant.user_id).collect();
+
+    tournament_repository::start_round(
+        db,
+        tournament,
+        1,
+        tournament.start_gameweek,
+        pair(user_ids),
+        vec![],
+    )
+    .await?;
+
+    if round_start == RoundStart::Over {
+        match_worker::settle_gameweek(db, &tournament.season, tournament.start_gameweek).await?;
+    }
+
+    Ok(())
+}
+
+async fn advance(
+    db: &DatabaseConnection,
+    tournament: &tournament::Model,
+) -> Result<(), Box<dyn Error>> {
+    let round = tournament.current_round;
+    let matches = tournament_repository::find_round_matches(db, tournament.id, round).await?;
+
+    if matches
+        .iter()
+        .any(|r#match| r#match.status != MatchStatus::Finished)
+    {
+        return Ok(());
+    }
+
+    let entrants = tournament_repository::find_entrants(db, tournament.id).await?;
+    let seeds = seeds(&entrants);
+
+    // (winner, loser) of every match, a draw goes to the better seed
+    let results: Vec<(i32, i32)> = matches
+        .iter()
+        .filter_map(|r#match| {
+            let (owner_id, opponent_id) = (r#match.owner_id, r#match.opponent_id?);
+
+            let winner_id = r#match.winner_id.unwrap_or_else(|| {
+                if seeds.get(&owner_id) <= seeds.get(&opponent_id) {
+                    owner_id
+                } else {
+                    opponent_id
+                }
+            });
+
+            if winner_id == owner_id {
+                Some((owner_id, opponent_id))
+            } else {
+                Some((opponent_id, owner_id))
+            }
+        })
+        .collect();
+
+    let loser_ids: Vec<i32> = results.iter().map(|(_, loser_id)| *loser_id).collect();
+
+    let remaining_ids: Vec<i32> = entrants
+        .iter()
+        .filter(|entrant| entrant.eliminated_round.is_none())
+        .map(|entrant| entrant.user_id)
+        .filter(|user_id| !loser_ids.contains(user_id))
+        .collect();
+
+    if let [champion_id] = remaining_ids[..] {
+        let runner_up_id = results
+            .iter()
+            .find(|(winner_id, _)| *winner_id == champion_id)
+            .map(|(_, loser_id)| *loser_id);
+
+        tournament_repository::finish_tournament(
+            db,
+            tournament,
+            champion_id,
+            runner_up_id,
+            loser_ids,
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let next_gameweek = tournament.start_gameweek + round;
+
+    let round_start = find_round_start(db, tournament, next_gameweek).await?;
+
+    if round_start == RoundStart::NotYet {
+        return Ok(());
+    }
+
+    tournament_repository::start_round(
+        db,
+        tournament,
+        round + 1,
+        next_gameweek,
+        pair(remaining_ids),
+        loser_ids,
+    )
+    .await?;
+
+    if round_start == RoundStart::Over {
+        match_worker::settle_gameweek(db, &tournament.season, next_gameweek).await?;
+    }
+
+    Ok(())
+}
+
+async fn find_round_start(
+    db: &DatabaseConnection,
+    tournament: &tournament::Model,
+    gameweek: i32,
+) -> Result<RoundStart, Box<dyn Error>> {
+    let event = event_status_repository::find_event(db, &tournament.season, gameweek).await?;
+
+    Ok(round_start(event.as_ref(), Utc::now()))
+}
+
+fn round_start(event: Option<&event_status::Model>, now: DateTime<Utc>) -> RoundStart {
+    match event {
+        None => RoundStart::NotYet,
+        Some(event) if event.deadline_time > now => RoundStart::NotYet,
+        Some(event)
+            if event.finished && event.data_checked && !event.is_previous && !event.is_current =>
+        {
+            RoundStart::Over
+        }
+        Some(_) => RoundStart::Open,
+    }
+}
+
+/// Lower is better, entrants are seeded by registration order.
+fn seeds(entrants: &[tournament_entrant::Model]) -> HashMap<i32, i32> {
+    entrants
+        .iter()
+        .map(|entrant| (entrant.user_id, entrant.id))
+        .collect()
+}
+
+/// Pair the best remaining seed with the worst one, the top seed gets a bye on an odd count.
+fn pair(mut user_ids: Vec<i32>) -> Vec<(i32, i32)> {
+    if user_ids.len() % 2 == 1 {
+        user_ids.remove(0);
+    }
+
+    let half = user_ids.len() / 2;
+
+    user_ids[..half]
+        .iter()
+        .zip(user_ids[half..].iter().rev())
+        .map(|(owner_id, opponent_id)| (*owner_id, *opponent_id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entrant(id: i32, user_id: i32) -> tournament_entrant::Model {
+        tournament_entrant::Model {
+            id,
+            tournament_id: 1,
+            user_id,
+            registered_at: Utc::now().fixed_offset(),
+            eliminated_round: None,
+        }
+    }
+
+    fn event(deadline_time: DateTime<Utc>, finished: bool) -> event_status::Model {
+        event_status::Model {
+            season: "2023/24".to_owned(),
+            gameweek: 10,
+            deadline_time: deadline_time.fixed_offset(),
+            finished,
+            data_checked: finished,
+            average_entry_score: 0,
+            highest_scoring_entry: 0,
+            deadline_time_epoch: 0,
+            is_previous: false,
+            is_current: false,
+            is_next: false,
+            name: "Gameweek 10".to_owned(),
+        }
+    }
+
+    #[test]
+    fn round_waits_for_its_deadline() {
+        let now = Utc::now();
+
+        assert_eq!(round_start(None, now), RoundStart::NotYet);
+        assert_eq!(
+            round_start(Some(&event(now + chrono::Duration::hours(1), false)), now),
+            RoundStart::NotYet
+        );
+    }
+
+    #[test]
+    fn round_of_the_current_gameweek_is_left_to_the_match_worker() {
+        let now = Utc::now();
+        let current = event_status::Model {
+            is_current: true,
+            ..event(now - chrono::Duration::hours(1), false)
+        };
+        let previous = event_status::Model {
+            is_previous: true,
+            ..event(now - chrono::Duration::days(7), true)
+        };
+
+        assert_eq!(round_start(Some(&current), now), RoundStart::Open);
+        assert_eq!(round_start(Some(&previous), now), RoundStart::Open);
+    }
+
+    #[test]
+    fn round_of_a_gameweek_already_over_is_settled_on_start() {
+        let now = Utc::now();
+        let over = event(now - chrono::Duration::days(14), true);
+
+        assert_eq!(round_start(Some(&over), now), RoundStart::Over);
+    }
+
+    #[test]
+    fn best_seed_meets_worst_seed() {
+        assert_eq!(pair(vec![1, 2, 3, 4]), vec![(1, 4), (2, 3)]);
+    }
+
+    #[test]
+    fn top_seed_gets_a_bye_on_odd_count() {
+        assert_eq!(pair(vec![1, 2, 3, 4, 5]), vec![(2, 5), (3, 4)]);
+        assert_eq!(pair(vec![7]), vec![]);
+    }
+
+    #[test]
+    fn seeds_follow_registration_order() {
+        let seeds = seeds(&[entrant(3, 30), entrant(1, 10), entrant(2, 20)]);
+
+        assert!(seeds[&10] < seeds[&20]);
+        assert!(seeds[&20] < seeds[&30]);
+    }
+}
@@ -0,0 +1,17 @@
+#!/bin/sh
+# Writes an Ed25519 signing key for local development, its kid is the file name.
+# Usage: scripts/generate-dev-key.sh [kid], run from the repository root.
+set -e
+
+dir="${JWT_KEYS_DIR:-keys}"
+kid="${1:-local}"
+
+mkdir -p "$dir"
+
+if [ -e "$dir/$kid.pem" ]; then
+    echo "$dir/$kid.pem already exists" >&2
+    exit 1
+fi
+
+openssl genpkey -algorithm ed25519 -out "$dir/$kid.pem"
+echo "wrote $dir/$kid.pem"
@@ -3,6 +3,9 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use database::models::{
+    MatchActionError, PaymentActionError, PromotionActionError, TournamentActionError,
+};
 use serde_json::json;
 
 pub enum AppError {
@@ -35,7 +38,7 @@ impl IntoResponse for AppError {
                         status_code,
                         format!(
                             "Error occured when sending http request, reason: {}",
-                            http_error.to_string()
+                            http_error
                         ),
                     ),
                 )
@@ -60,9 +63,13 @@ impl<A> From<RejectedApi> for Result<A, AppError> {
     }
 }
 
+#[allow(clippy::enum_variant_names)]
 pub enum RejectedApi {
     AuthenticationError(String),
+    PermissionDenied(String),
     ClientError(String),
+    /// A genuine third party token that was issued to another app.
+    AudienceMismatch(String),
     InternalError(String),
 }
 
@@ -76,12 +83,24 @@ impl IntoResponse for RejectedApi {
             )
                 .into_response(),
 
+            PermissionDenied(reason) => (
+                StatusCode::FORBIDDEN,
+                to_json(StatusCode::FORBIDDEN, reason),
+            )
+                .into_response(),
+
             ClientError(reason) => (
                 StatusCode::BAD_REQUEST,
                 to_json(StatusCode::BAD_REQUEST, reason),
             )
                 .into_response(),
 
+            AudienceMismatch(reason) => (
+                StatusCode::UNAUTHORIZED,
+                to_json(StatusCode::UNAUTHORIZED, reason),
+            )
+                .into_response(),
+
             InternalError(reason) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 to_json(StatusCode::INTERNAL_SERVER_ERROR, reason),
@@ -101,6 +120,100 @@ impl IntoAppError for services::Error {
     }
 }
 
+impl IntoAppError for MatchActionError {
+    fn into_app_error(self) -> AppError {
+        use MatchActionError::*;
+        let rejection = match self {
+            Database(db_error) => return AppError::Execution(db_error.into()),
+            MatchNotFound => RejectedApi::ClientError("not found match".into()),
+            UserNotFound => RejectedApi::AuthenticationError("not found user".into()),
+            NotOpen => RejectedApi::ClientError("the match is not for next round".into()),
+            Full => RejectedApi::ClientError("the match is full".into()),
+            OwnMatch => RejectedApi::ClientError("can not join the own match".into()),
+            NotOwner => RejectedApi::ClientError("the match is not yours".into()),
+            NotInvited => RejectedApi::ClientError("the match is private".into()),
+            NotChallenged => {
+                RejectedApi::ClientError("the match is a challenge for another user".into())
+            }
+            DeadlinePassed => {
+                RejectedApi::ClientError("the deadline of the gameweek has passed".into())
+            }
+            NotEnoughDCoin => RejectedApi::ClientError("not d_coin enough".into()),
+            OutOfRatingBand => {
+                RejectedApi::ClientError("your rating is out of the match rating band".into())
+            }
+        };
+
+        AppError::Rejection(rejection)
+    }
+}
+
+impl IntoAppError for TournamentActionError {
+    fn into_app_error(self) -> AppError {
+        use TournamentActionError::*;
+        let rejection = match self {
+            Database(db_error) => return AppError::Execution(db_error.into()),
+            TournamentNotFound => RejectedApi::ClientError("not found tournament".into()),
+            UserNotFound => RejectedApi::AuthenticationError("not found user".into()),
+            RegistrationClosed => {
+                RejectedApi::ClientError("the tournament registration is closed".into())
+            }
+            Full => RejectedApi::ClientError("the tournament is full".into()),
+            AlreadyRegistered => {
+                RejectedApi::ClientError("already registered for the tournament".into())
+            }
+            NotEnoughDCoin => RejectedApi::ClientError("not d_coin enough".into()),
+        };
+
+        AppError::Rejection(rejection)
+    }
+}
+
+impl IntoAppError for PaymentActionError {
+    fn into_app_error(self) -> AppError {
+        use PaymentActionError::*;
+        let rejection = match self {
+            Database(db_error) => return AppError::Execution(db_error.into()),
+            PaymentNotFound => RejectedApi::ClientError("not found payment".into()),
+            ProviderMismatch => {
+                RejectedApi::ClientError("the payment belongs to another provider".into())
+            }
+        };
+
+        AppError::Rejection(rejection)
+    }
+}
+
+impl IntoAppError for PromotionActionError {
+    fn into_app_error(self) -> AppError {
+        use PromotionActionError::*;
+        let rejection = match self {
+            Database(db_error) => return AppError::Execution(db_error.into()),
+            CouponNotFound => RejectedApi::ClientError("not found coupon".into()),
+            CouponCodeTaken => RejectedApi::ClientError("the coupon code is already taken".into()),
+            CouponExpired => RejectedApi::ClientError("the coupon has expired".into()),
+            CouponExhausted => RejectedApi::ClientError("the coupon has been used up".into()),
+            AlreadyRedeemed => RejectedApi::ClientError("already redeemed the coupon".into()),
+            UserNotFound => RejectedApi::AuthenticationError("not found user".into()),
+        };
+
+        AppError::Rejection(rejection)
+    }
+}
+
+impl IntoAppError for services::payment::PaymentError {
+    fn into_app_error(self) -> AppError {
+        use services::payment::PaymentError::*;
+        let rejection = match self {
+            InvalidSignature => RejectedApi::AuthenticationError(self.to_string()),
+            InvalidPayload(_) => RejectedApi::ClientError(self.to_string()),
+            Provider(_) => RejectedApi::InternalError(self.to_string()),
+        };
+
+        AppError::Rejection(rejection)
+    }
+}
+
 pub fn to_json(code: StatusCode, message: String) -> Json<serde_json::Value> {
     Json(json!({
         "code": code.as_u16(),
@@ -108,3 +221,17 @@ pub fn to_json(code: StatusCode, message: String) -> Json<serde_json::Value> {
         "status": code.to_string()
     }))
 }
+
+impl IntoAppError for services::identity::IdentityError {
+    fn into_app_error(self) -> AppError {
+        use services::identity::IdentityError::*;
+        let rejection = match self {
+            UnknownProvider(_) => RejectedApi::ClientError(self.to_string()),
+            InvalidToken(_) => RejectedApi::AuthenticationError(self.to_string()),
+            AudienceMismatch(_) => RejectedApi::AudienceMismatch(self.to_string()),
+            Request(err) => return AppError::SurfRequest(err),
+        };
+
+        AppError::Rejection(rejection)
+    }
+}
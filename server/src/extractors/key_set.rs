@@ -0,0 +1,253 @@
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    errors::ErrorKind,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+    Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use ring::{
+    rsa::PublicKeyComponents,
+    signature::{Ed25519KeyPair, KeyPair, RsaKeyPair},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{env, fs, path::Path};
+
+struct SigningKey {
+    kid: String,
+    algorithm: Algorithm,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    jwk: Jwk,
+}
+
+/// Keys of the tokens we issue, loaded once at startup.
+///
+/// Access tokens are signed with RS256 or EdDSA so other services can verify them
+/// from `/.well-known/jwks.json`. Every PKCS#8 `<kid>.pem` of `JWT_KEYS_DIR` is published
+/// and accepted, only `JWT_SIGNING_KEY_ID` signs. To rotate, add the new key, switch
+/// the signing key once it is published, and remove the old one after the access tokens
+/// it signed have expired.
+///
+/// Locally, `scripts/generate-dev-key.sh` writes the Ed25519 key the `.env` signs with.
+///
+/// Renew tokens are only ever read by us, they stay on HS256 with `JWT_REFRESH_SECRET`.
+pub struct KeySet {
+    keys: Vec<SigningKey>,
+    signing: usize,
+    renew_encoding_key: EncodingKey,
+    renew_decoding_key: DecodingKey,
+}
+
+impl KeySet {
+    pub fn from_env() -> Result<Self> {
+        let dir = env::var("JWT_KEYS_DIR").context("JWT_KEYS_DIR must be set.")?;
+        let signing_kid =
+            env::var("JWT_SIGNING_KEY_ID").context("JWT_SIGNING_KEY_ID must be set.")?;
+        let refresh_secret =
+            env::var("JWT_REFRESH_SECRET").context("JWT_REFRESH_SECRET must be set.")?;
+
+        let keys = read_keys(&dir)?;
+
+        let signing = keys
+            .iter()
+            .position(|key| key.kid == signing_kid)
+            .ok_or_else(|| anyhow!("signing key {signing_kid} is not in {dir}"))?;
+
+        Ok(Self {
+            keys,
+            signing,
+            renew_encoding_key: EncodingKey::from_secret(refresh_secret.as_bytes()),
+            renew_decoding_key: DecodingKey::from_secret(refresh_secret.as_bytes()),
+        })
+    }
+
+    pub fn encode<T: Serialize>(&self, claims: &T) -> jsonwebtoken::errors::Result<String> {
+        let key = &self.keys[self.signing];
+
+        let mut header = Header::new(key.algorithm);
+        header.kid = Some(key.kid.clone());
+
+        jsonwebtoken::encode(&header, claims, &key.encoding_key)
+    }
+
+    /// Accept a token signed by any published key, picked by its `kid`.
+    pub fn decode<T: DeserializeOwned>(&self, token: &str) -> jsonwebtoken::errors::Result<T> {
+        let header = jsonwebtoken::decode_header(token)?;
+
+        let key = header
+            .kid
+            .and_then(|kid| self.keys.iter().find(|key| key.kid == kid))
+            .ok_or(ErrorKind::InvalidToken)?;
+
+        jsonwebtoken::decode(token, &key.decoding_key, &Validation::new(key.algorithm))
+            .map(|token_data| token_data.claims)
+    }
+
+    pub fn encode_renew<T: Serialize>(
+        &self,
+        sub_claims: &T,
+    ) -> jsonwebtoken::errors::Result<String> {
+        jsonwebtoken::encode(
+            &Header::new(Algorithm::HS256),
+            sub_claims,
+            &self.renew_encoding_key,
+        )
+    }
+
+    pub fn decode_renew<T: DeserializeOwned>(
+        &self,
+        token: &str,
+    ) -> jsonwebtoken::errors::Result<T> {
+        jsonwebtoken::decode(token, &self.renew_decoding_key, &Validation::default())
+            .map(|token_data| token_data.claims)
+    }
+
+    pub fn jwks(&self) -> JwkSet {
+        JwkSet {
+            keys: self.keys.iter().map(|key| key.jwk.clone()).collect(),
+        }
+    }
+}
+
+/// Every `<kid>.pem` of `dir`, sorted by kid.
+fn read_keys(dir: &str) -> Result<Vec<SigningKey>> {
+    let entries = fs::read_dir(dir).with_context(|| {
+        format!("cannot read JWT_KEYS_DIR {dir}, run scripts/generate-dev-key.sh for a local key")
+    })?;
+
+    let mut keys = vec![];
+
+    for entry in entries {
+        let path = entry?.path();
+
+        if path.extension().is_some_and(|extension| extension == "pem") {
+            keys.push(SigningKey::from_file(&path)?);
+        }
+    }
+
+    if keys.is_empty() {
+        bail!("no <kid>.pem key in {dir}, run scripts/generate-dev-key.sh for a local key");
+    }
+
+    keys.sort_by(|a, b| a.kid.cmp(&b.kid));
+
+    Ok(keys)
+}
+
+impl SigningKey {
+    /// The key id is the file name without its extension.
+    fn from_file(path: &Path) -> Result<Self> {
+        let kid = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .ok_or_else(|| anyhow!("{} has no file name", path.display()))?;
+
+        let pem = fs::read(path)?;
+
+        Self::from_pem(kid, &pem).with_context(|| format!("invalid key {}", path.display()))
+    }
+
+    fn from_pem(kid: String, pem: &[u8]) -> Result<Self> {
+        let parsed = pem::parse(pem)?;
+
+        if parsed.tag() != "PRIVATE KEY" {
+            bail!("expected a PKCS#8 private key, found {}", parsed.tag());
+        }
+
+        let (algorithm, key_algorithm, parameters, encoding_key) = if let Ok(key_pair) =
+            RsaKeyPair::from_pkcs8(parsed.contents())
+        {
+            let components = PublicKeyComponents::<Vec<u8>>::from(key_pair.public());
+
+            (
+                Algorithm::RS256,
+                KeyAlgorithm::RS256,
+                AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: URL_SAFE_NO_PAD.encode(components.n),
+                    e: URL_SAFE_NO_PAD.encode(components.e),
+                }),
+                EncodingKey::from_rsa_pem(pem)?,
+            )
+        } else if let Ok(key_pair) = Ed25519KeyPair::from_pkcs8_maybe_unchecked(parsed.contents()) {
+            (
+                Algorithm::EdDSA,
+                KeyAlgorithm::EdDSA,
+                AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                    key_type: OctetKeyPairType::OctetKeyPair,
+                    curve: EllipticCurve::Ed25519,
+                    x: URL_SAFE_NO_PAD.encode(key_pair.public_key()),
+                }),
+                EncodingKey::from_ed_pem(pem)?,
+            )
+        } else {
+            bail!("only RSA and Ed25519 keys are supported");
+        };
+
+        let jwk = Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_algorithm: Some(key_algorithm),
+                key_id: Some(kid.clone()),
+                ..Default::default()
+            },
+            algorithm: parameters,
+        };
+
+        Ok(Self {
+            decoding_key: DecodingKey::from_jwk(&jwk)?,
+            kid,
+            algorithm,
+            encoding_key,
+            jwk,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = env::temp_dir().join(format!("key_set_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn missing_dir_points_to_the_dev_key_script() {
+        let err = read_keys("/nonexistent/keys").err().unwrap();
+
+        assert!(err.to_string().contains("generate-dev-key.sh"));
+    }
+
+    #[test]
+    fn dir_without_keys_is_rejected() {
+        let dir = temp_dir("empty");
+        fs::write(dir.join("notes.txt"), "not a key").unwrap();
+
+        let err = read_keys(dir.to_str().unwrap()).err().unwrap();
+
+        assert!(err.to_string().contains("no <kid>.pem key"));
+    }
+
+    #[test]
+    fn keys_are_named_after_their_file() {
+        let dir = temp_dir("ed25519");
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let pem = pem::encode(&pem::Pem::new("PRIVATE KEY", pkcs8.as_ref()));
+        fs::write(dir.join("local.pem"), pem).unwrap();
+
+        let keys = read_keys(dir.to_str().unwrap()).unwrap();
+
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].kid, "local");
+        assert_eq!(keys[0].algorithm, Algorithm::EdDSA);
+    }
+}
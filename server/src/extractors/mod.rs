@@ -1,3 +1,4 @@
+pub mod key_set;
 pub mod security;
 pub mod state;
 pub mod validator;
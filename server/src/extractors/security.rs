@@ -1,71 +1,144 @@
-use crate::error::RejectedApi;
-use axum::{async_trait, extract::FromRequestParts, http::request::Parts, RequestPartsExt};
+use crate::{
+    error::{AppError, RejectedApi},
+    extractors::key_set::KeySet,
+    session::is_access_token_revoked,
+};
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts},
+    http::request::Parts,
+    RequestPartsExt,
+};
 use axum_extra::{
     headers::{authorization::Bearer, Authorization},
     TypedHeader,
 };
 use chrono::Utc;
-use database::entities::user::Model as User;
+use database::{
+    entities::user::Model as User, repositories::user_repository, sea_orm::DatabaseConnection,
+};
 use jsonwebtoken::errors::ErrorKind;
-use jsonwebtoken::{DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
 
+/// Claims of an access token, `sid` is the session it was issued to.
 #[derive(Deserialize, Serialize)]
 pub struct Claims {
     pub exp: u32,
     pub id: i32,
+    pub jti: String,
+    pub sid: String,
 }
 
+/// Claims of a renew token, every token rotated from the same sign in shares its `family`.
 #[derive(Deserialize, Serialize)]
 pub struct SubClaims {
     pub exp: u32,
     pub sub: i32,
+    pub jti: String,
+    pub family: String,
 }
 
 pub struct Guard(pub Claims);
 
+/// Like `Guard` but only lets admins through.
+pub struct AdminGuard(pub Claims);
+
 #[async_trait]
 impl<S> FromRequestParts<S> for Guard
 where
     S: Send + Sync,
+    deadpool_redis::Pool: FromRef<S>,
+    Arc<KeySet>: FromRef<S>,
 {
     type Rejection = RejectedApi;
 
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        let access_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set.");
-
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let bearer = parts
             .extract::<TypedHeader<Authorization<Bearer>>>()
             .await
             .map_err(|_| RejectedApi::AuthenticationError("Missing Authorization".into()))?;
 
-        jsonwebtoken::decode::<Claims>(
-            bearer.token(),
-            &DecodingKey::from_secret(access_secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map_err(|err| match err.kind() {
-            ErrorKind::ExpiredSignature => RejectedApi::AuthenticationError("Expired token".into()),
-            _ => RejectedApi::AuthenticationError("Invalid token".into()),
-        })
-        .map(|token_data| Self(token_data.claims))
+        let claims = Arc::<KeySet>::from_ref(state)
+            .decode::<Claims>(bearer.token())
+            .map_err(|err| match err.kind() {
+                ErrorKind::ExpiredSignature => {
+                    RejectedApi::AuthenticationError("Expired token".into())
+                }
+                _ => RejectedApi::AuthenticationError("Invalid token".into()),
+            })?;
+
+        let mut redis_conn = deadpool_redis::Pool::from_ref(state)
+            .get()
+            .await
+            .map_err(|err| RejectedApi::InternalError(err.to_string()))?;
+
+        let is_revoked = is_access_token_revoked(&claims.jti, &mut redis_conn)
+            .await
+            .map_err(|err| RejectedApi::InternalError(err.to_string()))?;
+
+        if is_revoked {
+            return Err(RejectedApi::AuthenticationError("Revoked token".into()));
+        }
+
+        Ok(Self(claims))
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AdminGuard
+where
+    S: Send + Sync,
+    DatabaseConnection: FromRef<S>,
+    deadpool_redis::Pool: FromRef<S>,
+    Arc<KeySet>: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Guard(claims) = Guard::from_request_parts(parts, state)
+            .await
+            .map_err(AppError::Rejection)?;
+
+        let user =
+            user_repository::find_by_id(&DatabaseConnection::from_ref(state), claims.id).await?;
+
+        if !user.is_some_and(|user| user.is_admin) {
+            return RejectedApi::PermissionDenied("admin only".into()).into();
+        }
+
+        Ok(Self(claims))
     }
 }
 
 impl Claims {
-    pub fn new(user: &User, expired: chrono::Duration) -> Self {
+    pub fn new(user: &User, session_id: &str, expired: chrono::Duration) -> Self {
         Self {
             id: user.id,
             exp: Utc::now().checked_add_signed(expired).unwrap().timestamp() as u32,
+            jti: Uuid::new_v4().simple().to_string(),
+            sid: session_id.to_owned(),
         }
     }
 }
 
 impl SubClaims {
+    /// Start a new family of renew tokens.
     pub fn new(sub: i32, expired: chrono::Duration) -> Self {
         Self {
             sub,
             exp: Utc::now().checked_add_signed(expired).unwrap().timestamp() as u32,
+            jti: Uuid::new_v4().simple().to_string(),
+            family: Uuid::new_v4().simple().to_string(),
+        }
+    }
+
+    /// The next renew token of the same family.
+    pub fn rotate(&self, expired: chrono::Duration) -> Self {
+        Self {
+            family: self.family.clone(),
+            ..Self::new(self.sub, expired)
         }
     }
 }
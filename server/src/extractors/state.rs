@@ -1,5 +1,6 @@
+use super::key_set::KeySet;
 use crate::error::AppError;
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use axum::{
     async_trait,
     extract::{FromRef, FromRequestParts},
@@ -7,16 +8,29 @@ use axum::{
 };
 use database::sea_orm::{ConnectOptions, Database, DatabaseConnection};
 use deadpool_redis::{Config, Runtime};
+use services::{
+    facebook::FacebookProvider,
+    google::GoogleProvider,
+    identity::{IdentityProviders, OidcProvider},
+    payment::{FakePaymentProvider, PaymentProvider},
+};
+use std::sync::Arc;
 
 pub type RedisConnection = deadpool_redis::Connection;
 
 pub struct Redis(pub RedisConnection);
 pub struct Postgres(pub DatabaseConnection);
+pub struct PaymentGateway(pub Arc<dyn PaymentProvider>);
+pub struct Keys(pub Arc<KeySet>);
+pub struct Identities(pub Arc<IdentityProviders>);
 
 #[derive(Clone)]
 pub struct AppState {
     pg_conn: DatabaseConnection,
     redis_pool: deadpool_redis::Pool,
+    payment_provider: Arc<dyn PaymentProvider>,
+    key_set: Arc<KeySet>,
+    identity_providers: Arc<IdentityProviders>,
 }
 
 #[async_trait]
@@ -44,11 +58,50 @@ where
         deadpool_redis::Pool::from_ref(state)
             .get()
             .await
-            .map(|conn| Self(conn))
+            .map(Self)
             .map_err(|err| err.into())
     }
 }
 
+#[async_trait]
+impl<S> FromRequestParts<S> for PaymentGateway
+where
+    S: Send + Sync,
+    Arc<dyn PaymentProvider>: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(Arc::<dyn PaymentProvider>::from_ref(state)))
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Keys
+where
+    S: Send + Sync,
+    Arc<KeySet>: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(Arc::<KeySet>::from_ref(state)))
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Identities
+where
+    S: Send + Sync,
+    Arc<IdentityProviders>: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(Arc::<IdentityProviders>::from_ref(state)))
+    }
+}
+
 impl FromRef<AppState> for DatabaseConnection {
     fn from_ref(app_state: &AppState) -> DatabaseConnection {
         app_state.pg_conn.clone()
@@ -61,8 +114,69 @@ impl FromRef<AppState> for deadpool_redis::Pool {
     }
 }
 
+impl FromRef<AppState> for Arc<dyn PaymentProvider> {
+    fn from_ref(app_state: &AppState) -> Arc<dyn PaymentProvider> {
+        app_state.payment_provider.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<KeySet> {
+    fn from_ref(app_state: &AppState) -> Arc<KeySet> {
+        app_state.key_set.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<IdentityProviders> {
+    fn from_ref(app_state: &AppState) -> Arc<IdentityProviders> {
+        app_state.identity_providers.clone()
+    }
+}
+
+/// The provider is never assumed, the fake one only runs when asked for by name.
+fn payment_provider() -> Result<Arc<dyn PaymentProvider>> {
+    let provider = std::env::var("PAYMENT_PROVIDER").context("PAYMENT_PROVIDER must be set.")?;
+
+    match provider.as_str() {
+        "fake" => Ok(Arc::new(FakePaymentProvider::new(
+            std::env::var("PAYMENT_WEBHOOK_SECRET")
+                .context("PAYMENT_WEBHOOK_SECRET must be set.")?,
+        ))),
+        other => bail!("unknown PAYMENT_PROVIDER {}", other),
+    }
+}
+
+/// Google and Facebook are always on, Apple and a generic OIDC issuer only when configured.
+fn identity_providers() -> IdentityProviders {
+    let mut providers = IdentityProviders::default();
+
+    providers.register(GoogleProvider::new(
+        &std::env::var("GOOGLE_CLIENT_ID").expect("GOOGLE_CLIENT_ID must be set."),
+    ));
+    providers.register(FacebookProvider::new(
+        &std::env::var("FACEBOOK_APP_ID").expect("FACEBOOK_APP_ID must be set."),
+        &std::env::var("FACEBOOK_APP_SECRET").expect("FACEBOOK_APP_SECRET must be set."),
+    ));
+
+    if let Ok(client_id) = std::env::var("APPLE_CLIENT_ID") {
+        providers.register(OidcProvider::apple(&client_id));
+    }
+
+    if let Ok(issuer) = std::env::var("OIDC_ISSUER") {
+        providers.register(OidcProvider::new(
+            &std::env::var("OIDC_PROVIDER_NAME").unwrap_or("oidc".to_owned()),
+            &issuer,
+            &std::env::var("OIDC_CLIENT_ID").expect("OIDC_CLIENT_ID must be set."),
+        ));
+    }
+
+    providers
+}
+
 impl AppState {
     pub async fn new(db_url: &str) -> Result<Self> {
+        let key_set = Arc::new(KeySet::from_env()?);
+        let payment_provider = payment_provider()?;
+
         let mut opt = ConnectOptions::new(db_url);
         opt.sqlx_logging(false);
 
@@ -76,6 +190,9 @@ impl AppState {
                     .map(|redis_pool| Self {
                         pg_conn,
                         redis_pool,
+                        payment_provider,
+                        key_set,
+                        identity_providers: Arc::new(identity_providers()),
                     })
             })
     }
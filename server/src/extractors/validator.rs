@@ -13,6 +13,7 @@ use validator::Validate;
 
 pub struct ValidatedQuery<Q>(pub Q);
 pub struct ValidatedPayload<P>(pub P);
+#[allow(dead_code)]
 pub struct ValidatedForm<F>(pub F);
 
 #[async_trait]
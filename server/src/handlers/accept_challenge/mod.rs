@@ -0,0 +1,21 @@
+use super::shared;
+use crate::{
+    error::{AppError, RejectedApi},
+    extractors::{security::Guard, state::Postgres},
+};
+use axum::extract::Path;
+use database::repositories::match_repository;
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    Guard(claims): Guard,
+    Path(match_id): Path<i32>,
+) -> Result<(), AppError> {
+    let r#match = match_repository::find_by_id(&db, match_id).await?;
+
+    let Some(r#match) = r#match.filter(|r#match| r#match.challenged_id == Some(claims.id)) else {
+        return RejectedApi::ClientError("not found challenge".to_owned()).into();
+    };
+
+    shared::join_match(&db, r#match, claims.id, None).await
+}
@@ -0,0 +1,16 @@
+use crate::{
+    error::{AppError, IntoAppError},
+    extractors::{security::Guard, state::Postgres},
+};
+use axum::extract::Path;
+use database::repositories::match_repository;
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    Guard(claims): Guard,
+    Path(match_id): Path<i32>,
+) -> Result<(), AppError> {
+    match_repository::cancel_match(&db, match_id, claims.id)
+        .await
+        .map_err(|err| err.into_app_error())
+}
@@ -0,0 +1,55 @@
+use crate::{
+    error::{AppError, IntoAppError},
+    extractors::{
+        security::Guard,
+        state::{PaymentGateway, Postgres},
+        validator::ValidatedPayload,
+    },
+    responses::payment::CheckoutResponse,
+};
+use axum::Json;
+use database::{entities::payment, repositories::payment_repository, sea_orm::Set};
+use serde::Deserialize;
+use validator::Validate;
+
+/// Price of a d_coin in cents.
+const D_COIN_PRICE: i64 = 10;
+
+#[derive(Deserialize, Validate)]
+pub struct Payload {
+    #[validate(range(min = 100, max = 100000))]
+    d_coin: i32,
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    PaymentGateway(provider): PaymentGateway,
+    Guard(claims): Guard,
+    ValidatedPayload(payload): ValidatedPayload<Payload>,
+) -> Result<Json<CheckoutResponse>, AppError> {
+    let amount = i64::from(payload.d_coin) * D_COIN_PRICE;
+
+    let intent = provider
+        .create_checkout(claims.id, payload.d_coin, amount)
+        .await
+        .map_err(|err| err.into_app_error())?;
+
+    payment_repository::create_payment(
+        &db,
+        payment::ActiveModel {
+            id: Set(intent.payment_id.clone()),
+            provider: Set(provider.name().to_owned()),
+            d_coin: Set(payload.d_coin),
+            amount: Set(amount),
+            user_id: Set(claims.id),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    Ok(Json(CheckoutResponse {
+        payment_id: intent.payment_id,
+        checkout_url: intent.checkout_url,
+        amount,
+    }))
+}
@@ -0,0 +1,55 @@
+use crate::{
+    error::{AppError, IntoAppError},
+    extractors::{security::AdminGuard, state::Postgres, validator::ValidatedPayload},
+};
+use axum::Json;
+use chrono::Utc;
+use database::{
+    entities::coupon, repositories::promotion_repository, sea_orm::prelude::DateTimeWithTimeZone,
+    sea_orm::Set,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use validator::Validate;
+
+static CODE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^[A-Za-z0-9_-]{4,32}$"#).unwrap());
+
+#[derive(Deserialize, Validate)]
+pub struct Payload {
+    /// Codes are case insensitive, they are stored upper case.
+    #[validate(regex = "CODE_PATTERN")]
+    code: String,
+
+    #[validate(range(min = 1))]
+    d_coin: i32,
+
+    /// Unlimited when omitted.
+    #[validate(range(min = 1))]
+    max_redemptions: Option<i32>,
+
+    expires_at: Option<DateTimeWithTimeZone>,
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    AdminGuard(claims): AdminGuard,
+    ValidatedPayload(payload): ValidatedPayload<Payload>,
+) -> Result<Json<coupon::Model>, AppError> {
+    let coupon = promotion_repository::create_coupon(
+        &db,
+        coupon::ActiveModel {
+            code: Set(payload.code.to_uppercase()),
+            d_coin: Set(payload.d_coin),
+            max_redemptions: Set(payload.max_redemptions),
+            expires_at: Set(payload.expires_at),
+            created_date: Set(Utc::now().fixed_offset()),
+            creator_id: Set(claims.id),
+            ..Default::default()
+        },
+    )
+    .await
+    .map_err(|err| err.into_app_error())?;
+
+    Ok(Json(coupon))
+}
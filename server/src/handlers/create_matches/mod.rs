@@ -1,16 +1,19 @@
 use crate::{
-    error::{AppError, RejectedApi},
+    error::{AppError, IntoAppError, RejectedApi},
     extractors::{security::Guard, state::Postgres, validator::ValidatedPayload},
+    responses::r#match::CreateMatchesResponse,
 };
+use axum::Json;
 use database::{
     entities::{
         r#match,
         sea_orm_active_enums::{ChipRule, TransferRule},
     },
-    repositories::{event_status_repository, match_repository, user_repository},
+    repositories::{event_status_repository, match_repository, rating_repository, user_repository},
     sea_orm::Set,
 };
 use serde::Deserialize;
+use uuid::Uuid;
 use validator::Validate;
 
 #[derive(Deserialize, Validate, Clone)]
@@ -29,21 +32,76 @@ pub struct Payload {
 
     #[validate(range(min = 1))]
     min_week_started: Option<i32>,
+
+    #[validate(range(min = 1))]
+    challenged_user_id: Option<i32>,
+
+    #[validate(range(min = 1))]
+    challenged_fpl_id: Option<i32>,
+
+    /// Matches start on the next gameweek and are scored up to this one.
+    #[validate(range(min = 1, max = 38))]
+    end_gameweek: Option<i32>,
+
+    /// Settled matches only move the players' ratings when opted in.
+    is_rated: Option<bool>,
+
+    /// Only players rated within this distance of the creator can join.
+    #[validate(range(min = 1))]
+    rating_band: Option<i32>,
 }
 
 pub async fn handler(
     Postgres(db): Postgres,
     Guard(claims): Guard,
     ValidatedPayload(payload): ValidatedPayload<Payload>,
-) -> Result<(), AppError> {
+) -> Result<Json<CreateMatchesResponse>, AppError> {
     let next_event = event_status_repository::find_next_event(&db).await?;
 
     let Some(next_event) = next_event else {
         return RejectedApi::InternalError("not found next gameweek".to_owned()).into();
     };
 
+    let end_gameweek = match payload.end_gameweek {
+        Some(end_gameweek) if end_gameweek < next_event.gameweek => {
+            return RejectedApi::ClientError(
+                "end gameweek must not be before the next gameweek".to_owned(),
+            )
+            .into();
+        }
+        Some(end_gameweek) if end_gameweek > next_event.gameweek => Some(end_gameweek),
+        _ => None,
+    };
+
     let total_d_coin = payload.quantity as i32 * payload.bet;
 
+    let challenged = match (payload.challenged_user_id, payload.challenged_fpl_id) {
+        (Some(_), Some(_)) => {
+            return RejectedApi::ClientError(
+                "challenge either a user id or a fpl id, not both".to_owned(),
+            )
+            .into();
+        }
+        (Some(user_id), None) => user_repository::find_by_id(&db, user_id).await?,
+        (None, Some(fpl_id)) => user_repository::find_by_fpl_id(&db, fpl_id).await?,
+        (None, None) => None,
+    };
+
+    let challenged_id = match challenged {
+        Some(challenged) if challenged.id == claims.id => {
+            return RejectedApi::ClientError("can not challenge yourself".to_owned()).into();
+        }
+        Some(challenged) => Some(challenged.id),
+        None if payload.challenged_user_id.is_some() || payload.challenged_fpl_id.is_some() => {
+            return RejectedApi::ClientError("not found challenged user".to_owned()).into();
+        }
+        None => None,
+    };
+
+    if challenged_id.is_some() && payload.quantity > 1 {
+        return RejectedApi::ClientError("a challenge must be a single match".to_owned()).into();
+    }
+
     {
         let user = user_repository::find_by_id(&db, claims.id).await?;
 
@@ -56,24 +114,47 @@ pub async fn handler(
         }
     }
 
-    let matches_vec = vec![1; payload.quantity as usize];
+    let is_private = payload.is_private.unwrap_or_default();
+
+    let (min_rating, max_rating) = match payload.rating_band {
+        Some(rating_band) => {
+            let rating = rating_repository::find_rating(&db, claims.id, &next_event.season).await?;
+
+            (Some(rating - rating_band), Some(rating + rating_band))
+        }
+        None => (None, None),
+    };
+
+    let invite_codes: Vec<Option<String>> = (0..payload.quantity)
+        .map(|_| is_private.then(|| Uuid::new_v4().simple().to_string()))
+        .collect();
 
-    let matches = matches_vec
-        .into_iter()
-        .map(|_| r#match::ActiveModel {
+    let matches = invite_codes
+        .iter()
+        .map(|invite_code| r#match::ActiveModel {
             bet_amount: Set(payload.bet),
             chip_rule: Set(payload.chip_rule.clone()),
             transfer_rule: Set(payload.transfer_rule.clone()),
             gameweek: Set(next_event.gameweek),
+            end_gameweek: Set(end_gameweek),
             owner_id: Set(claims.id),
-            season: Set("23-24".to_owned()),
-            is_private: Set(payload.is_private.unwrap_or_default()),
+            season: Set(next_event.season.clone()),
+            is_private: Set(is_private),
+            invite_code: Set(invite_code.clone()),
+            challenged_id: Set(challenged_id),
+            min_week_started: Set(payload.min_week_started),
+            is_rated: Set(payload.is_rated.unwrap_or_default()),
+            min_rating: Set(min_rating),
+            max_rating: Set(max_rating),
             ..Default::default()
         })
         .collect::<Vec<r#match::ActiveModel>>();
 
     match_repository::create_matches(&db, claims.id, matches, next_event.gameweek, total_d_coin)
-        .await?;
+        .await
+        .map_err(|err| err.into_app_error())?;
 
-    Ok(())
+    Ok(Json(CreateMatchesResponse {
+        invite_codes: invite_codes.into_iter().flatten().collect(),
+    }))
 }
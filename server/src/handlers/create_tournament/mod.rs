@@ -0,0 +1,87 @@
+use crate::{
+    error::{AppError, RejectedApi},
+    extractors::{security::Guard, state::Postgres, validator::ValidatedPayload},
+};
+use axum::Json;
+use database::{
+    entities::{
+        sea_orm_active_enums::{ChipRule, TransferRule},
+        tournament,
+    },
+    repositories::{event_status_repository, tournament_repository},
+    sea_orm::Set,
+};
+use serde::Deserialize;
+use validator::Validate;
+
+const LAST_GAMEWEEK: i32 = 38;
+
+#[derive(Deserialize, Validate)]
+pub struct Payload {
+    #[validate(length(min = 1, max = 64))]
+    name: String,
+
+    #[validate(range(min = 0))]
+    entry_fee: i32,
+
+    #[validate(range(min = 2, max = 256))]
+    max_entrants: i32,
+
+    #[validate(range(min = 1, max = 38))]
+    start_gameweek: Option<i32>,
+
+    chip_rule: ChipRule,
+
+    transfer_rule: TransferRule,
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    Guard(claims): Guard,
+    ValidatedPayload(payload): ValidatedPayload<Payload>,
+) -> Result<Json<tournament::Model>, AppError> {
+    let next_event = event_status_repository::find_next_event(&db).await?;
+
+    let Some(next_event) = next_event else {
+        return RejectedApi::InternalError("not found next gameweek".to_owned()).into();
+    };
+
+    let start_gameweek = payload.start_gameweek.unwrap_or(next_event.gameweek);
+
+    if start_gameweek < next_event.gameweek {
+        return RejectedApi::ClientError(format!(
+            "the tournament can not start before gameweek {}",
+            next_event.gameweek
+        ))
+        .into();
+    }
+
+    // one gameweek per round, the final must be played within the season
+    let rounds = (payload.max_entrants as u32).next_power_of_two().ilog2() as i32;
+
+    if start_gameweek + rounds - 1 > LAST_GAMEWEEK {
+        return RejectedApi::ClientError(format!(
+            "{} rounds starting from gameweek {} do not fit in the season",
+            rounds, start_gameweek
+        ))
+        .into();
+    }
+
+    let tournament = tournament_repository::create_tournament(
+        &db,
+        tournament::ActiveModel {
+            name: Set(payload.name),
+            season: Set(next_event.season),
+            entry_fee: Set(payload.entry_fee),
+            max_entrants: Set(payload.max_entrants),
+            start_gameweek: Set(start_gameweek),
+            chip_rule: Set(payload.chip_rule),
+            transfer_rule: Set(payload.transfer_rule),
+            creator_id: Set(claims.id),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    Ok(Json(tournament))
+}
@@ -0,0 +1,7 @@
+use crate::extractors::state::Keys;
+use axum::Json;
+use jsonwebtoken::jwk::JwkSet;
+
+pub async fn handler(Keys(key_set): Keys) -> Json<JwkSet> {
+    Json(key_set.jwks())
+}
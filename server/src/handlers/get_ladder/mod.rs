@@ -0,0 +1,52 @@
+use crate::{
+    error::{AppError, RejectedApi},
+    extractors::{state::Postgres, validator::ValidatedQuery},
+    responses::PaginationResponse,
+};
+use axum::Json;
+use database::{
+    models::RatingOnLadder,
+    repositories::{rating_repository, season_repository},
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use validator::Validate;
+
+static SEASON_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^\d{4}-\d{4}$"#).unwrap());
+
+#[derive(Deserialize, Validate)]
+pub struct QueryParams {
+    #[validate(range(min = 1))]
+    page: u64,
+
+    #[validate(range(min = 1, max = 300))]
+    take: u64,
+
+    /// The current season when omitted.
+    #[validate(regex = "SEASON_PATTERN")]
+    season: Option<String>,
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    ValidatedQuery(QueryParams { page, take, season }): ValidatedQuery<QueryParams>,
+) -> Result<Json<PaginationResponse<RatingOnLadder>>, AppError> {
+    let season = match season {
+        Some(season) => season,
+        None => match season_repository::find_current_season(&db).await? {
+            Some(current_season) => current_season.name,
+            None => {
+                return RejectedApi::InternalError("not found current season".to_owned()).into()
+            }
+        },
+    };
+
+    let (ratings, total) = rating_repository::find_ladder(&db, &season, page, take).await?;
+
+    Ok(Json(PaginationResponse {
+        nodes: ratings,
+        page,
+        total,
+    }))
+}
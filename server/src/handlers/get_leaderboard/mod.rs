@@ -0,0 +1,85 @@
+use crate::{
+    error::{AppError, RejectedApi},
+    extractors::{state::Postgres, validator::ValidatedQuery},
+    responses::PaginationResponse,
+};
+use axum::Json;
+use database::{
+    models::{FindLeaderboardParams, LeaderboardEntry, LeaderboardMetric, MIN_GAMES_FOR_WIN_RATE},
+    repositories::leaderboard_repository,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use validator::Validate;
+
+static SEASON_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^\d{4}-\d{4}$"#).unwrap());
+
+#[derive(Deserialize, Validate)]
+pub struct QueryParams {
+    metric: LeaderboardMetric,
+
+    #[validate(range(min = 1))]
+    page: u64,
+
+    #[validate(range(min = 1, max = 300))]
+    take: u64,
+
+    #[validate(regex = "SEASON_PATTERN")]
+    season: Option<String>,
+
+    #[validate(range(min = 1, max = 38))]
+    from_gameweek: Option<i32>,
+
+    #[validate(range(min = 1, max = 38))]
+    to_gameweek: Option<i32>,
+
+    #[validate(range(min = 1))]
+    min_games: Option<i64>,
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    ValidatedQuery(QueryParams {
+        metric,
+        page,
+        take,
+        season,
+        from_gameweek,
+        to_gameweek,
+        min_games,
+    }): ValidatedQuery<QueryParams>,
+) -> Result<Json<PaginationResponse<LeaderboardEntry>>, AppError> {
+    if from_gameweek
+        .zip(to_gameweek)
+        .is_some_and(|(from_gameweek, to_gameweek)| from_gameweek > to_gameweek)
+    {
+        return RejectedApi::ClientError("from_gameweek must not be after to_gameweek".to_owned())
+            .into();
+    }
+
+    let min_games = min_games.unwrap_or(match metric {
+        LeaderboardMetric::WinRate => MIN_GAMES_FOR_WIN_RATE,
+        _ => 1,
+    });
+
+    let (entries, total) = leaderboard_repository::find_leaderboard(
+        &db,
+        FindLeaderboardParams {
+            metric,
+            season,
+            from_gameweek,
+            to_gameweek,
+            min_games,
+            page,
+            take,
+        },
+    )
+    .await?;
+
+    Ok(Json(PaginationResponse {
+        nodes: entries,
+        page,
+        total,
+    }))
+}
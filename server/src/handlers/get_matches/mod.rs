@@ -7,7 +7,7 @@ use axum::Json;
 use database::{
     entities::sea_orm_active_enums::MatchStatus,
     models::{FindMatchesParams, MatchWithOwnerOpponentAndWinner},
-    repositories::match_repository,
+    repositories::{event_status_repository, match_repository, rating_repository},
 };
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -20,6 +20,7 @@ static SEASON_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^\d{4}-\d{4}$"#)
 enum FindMatchesOption {
     MyMatches,
     MyMatchesAndMyJoinedMatches,
+    MyChallenges,
     FlashMatch,
 }
 
@@ -37,6 +38,13 @@ pub struct QueryParams {
 
     #[validate(regex = "SEASON_PATTERN")]
     season: Option<String>,
+
+    rated_only: Option<bool>,
+
+    /// Rating band of the match owners, only applied to `FlashMatch`.
+    min_rating: Option<i32>,
+
+    max_rating: Option<i32>,
 }
 
 pub async fn handler(
@@ -48,19 +56,34 @@ pub async fn handler(
         status,
         page,
         season,
+        rated_only,
+        min_rating,
+        max_rating,
     }): ValidatedQuery<QueryParams>,
 ) -> Result<Json<PaginationResponse<MatchWithOwnerOpponentAndWinner>>, AppError> {
-    let mut find_params = FindMatchesParams::default();
-
-    find_params.page = page;
-    find_params.take = take;
-    find_params.season = season;
-    find_params.status = status;
+    let mut find_params = FindMatchesParams {
+        page,
+        take,
+        season,
+        status,
+        rated_only: rated_only.unwrap_or_default(),
+        ..Default::default()
+    };
 
     match option {
         FindMatchesOption::FlashMatch => {
             find_params.status = MatchStatus::Next;
             find_params.exclude_created_by = Some(claims.id);
+            find_params.exclude_private = true;
+            find_params.exclude_challenges = true;
+            find_params.min_owner_rating = min_rating;
+            find_params.max_owner_rating = max_rating;
+
+            // hide the matches whose rating band the user can not join
+            if let Some(next_event) = event_status_repository::find_next_event(&db).await? {
+                find_params.joinable_with_rating =
+                    Some(rating_repository::find_rating(&db, claims.id, &next_event.season).await?);
+            }
         }
         FindMatchesOption::MyMatches => {
             find_params.created_by = Some(claims.id);
@@ -68,6 +91,9 @@ pub async fn handler(
         FindMatchesOption::MyMatchesAndMyJoinedMatches => {
             find_params.joined_by_or_created = Some(claims.id)
         }
+        FindMatchesOption::MyChallenges => {
+            find_params.challenged = Some(claims.id);
+        }
     };
 
     let (matches, total) = match_repository::find_matches(&db, find_params).await?;
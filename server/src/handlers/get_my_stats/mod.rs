@@ -0,0 +1,30 @@
+use crate::{
+    error::AppError,
+    extractors::{security::Guard, state::Postgres, validator::ValidatedQuery},
+};
+use axum::Json;
+use database::{models::UserStats, repositories::match_repository};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use validator::Validate;
+
+static SEASON_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^\d{4}-\d{4}$"#).unwrap());
+
+#[derive(Deserialize, Validate)]
+pub struct QueryParams {
+    /// Every season when omitted.
+    #[validate(regex = "SEASON_PATTERN")]
+    season: Option<String>,
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    Guard(claims): Guard,
+    ValidatedQuery(QueryParams { season }): ValidatedQuery<QueryParams>,
+) -> Result<Json<UserStats>, AppError> {
+    let matches =
+        match_repository::find_all_finished_by_participant(&db, claims.id, season).await?;
+
+    Ok(Json(UserStats::from_matches(claims.id, &matches)))
+}
@@ -0,0 +1,9 @@
+use crate::{error::AppError, extractors::state::Postgres};
+use axum::Json;
+use database::{entities::season, repositories::season_repository};
+
+pub async fn handler(Postgres(db): Postgres) -> Result<Json<Vec<season::Model>>, AppError> {
+    let seasons = season_repository::find_all(&db).await?;
+
+    Ok(Json(seasons))
+}
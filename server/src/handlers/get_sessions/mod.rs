@@ -0,0 +1,27 @@
+use crate::{
+    error::AppError,
+    extractors::{security::Guard, state::Redis},
+    responses::auth::SessionResponse,
+    session::find_sessions,
+};
+use axum::Json;
+
+pub async fn handler(
+    Redis(mut redis_conn): Redis,
+    Guard(claims): Guard,
+) -> Result<Json<Vec<SessionResponse>>, AppError> {
+    let sessions = find_sessions(claims.id, &mut redis_conn).await?;
+
+    Ok(Json(
+        sessions
+            .into_iter()
+            .map(|session| SessionResponse {
+                current: session.id == claims.sid,
+                id: session.id,
+                device: session.device,
+                created_at: session.created_at,
+                last_used_at: session.last_used_at,
+            })
+            .collect(),
+    ))
+}
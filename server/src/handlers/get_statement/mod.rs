@@ -0,0 +1,126 @@
+use crate::{
+    error::{AppError, RejectedApi},
+    extractors::{security::Guard, state::Postgres, validator::ValidatedQuery},
+    responses::transaction::StatementResponse,
+};
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{Months, NaiveDate};
+use database::{
+    models::{FindTransactionsParams, TransactionWithBalance},
+    repositories::transaction_repository,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use validator::Validate;
+
+static MONTH_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^\d{4}-(0[1-9]|1[0-2])$"#).unwrap());
+
+#[derive(Deserialize, Default)]
+enum StatementFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct QueryParams {
+    /// Formatted as `2024-01`, the month is taken in UTC.
+    #[validate(regex = "MONTH_PATTERN")]
+    month: String,
+
+    format: Option<StatementFormat>,
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    Guard(claims): Guard,
+    ValidatedQuery(QueryParams { month, format }): ValidatedQuery<QueryParams>,
+) -> Result<Response, AppError> {
+    let Ok(month_start) = NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d") else {
+        return RejectedApi::ClientError("invalid month".to_owned()).into();
+    };
+
+    let Some(next_month_start) = month_start.checked_add_months(Months::new(1)) else {
+        return RejectedApi::ClientError("invalid month".to_owned()).into();
+    };
+
+    let from = month_start
+        .and_time(Default::default())
+        .and_utc()
+        .fixed_offset();
+    let to = next_month_start
+        .and_time(Default::default())
+        .and_utc()
+        .fixed_offset();
+
+    let opening_balance = transaction_repository::find_transactions(
+        &db,
+        FindTransactionsParams {
+            owner_id: claims.id,
+            to: Some(from),
+            take: Some(1),
+            ..Default::default()
+        },
+    )
+    .await?
+    .first()
+    .map_or(0, |transaction| transaction.balance);
+
+    let mut transactions = transaction_repository::find_transactions(
+        &db,
+        FindTransactionsParams {
+            owner_id: claims.id,
+            from: Some(from),
+            to: Some(to),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    // a statement reads from the oldest transaction
+    transactions.reverse();
+
+    let (total_credit, total_debit) = transactions
+        .iter()
+        .map(TransactionWithBalance::signed_d_coin)
+        .fold((0, 0), |(credit, debit), d_coin| {
+            if d_coin > 0 {
+                (credit + d_coin, debit)
+            } else {
+                (credit, debit - d_coin)
+            }
+        });
+
+    let statement = StatementResponse {
+        closing_balance: transactions
+            .last()
+            .map_or(opening_balance, |transaction| transaction.balance),
+        month,
+        opening_balance,
+        total_credit,
+        total_debit,
+        transactions,
+    };
+
+    let response = match format.unwrap_or_default() {
+        StatementFormat::Json => Json(statement).into_response(),
+        StatementFormat::Csv => (
+            [
+                (header::CONTENT_TYPE, "text/csv".to_owned()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"statement-{}.csv\"", statement.month),
+                ),
+            ],
+            statement.to_csv(),
+        )
+            .into_response(),
+    };
+
+    Ok(response)
+}
@@ -0,0 +1,71 @@
+use crate::{
+    error::AppError,
+    extractors::{security::Guard, state::Postgres, validator::ValidatedQuery},
+    responses::KeysetPaginationResponse,
+};
+use axum::Json;
+use database::{
+    entities::sea_orm_active_enums::{TransactionFlag, TransactionType},
+    models::{FindTransactionsParams, TransactionWithBalance},
+    repositories::transaction_repository,
+    sea_orm::prelude::DateTimeWithTimeZone,
+};
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate)]
+pub struct QueryParams {
+    #[validate(range(min = 1, max = 300))]
+    take: u64,
+
+    #[validate(range(min = 1))]
+    before_id: Option<i32>,
+
+    r#type: Option<TransactionType>,
+
+    flag: Option<TransactionFlag>,
+
+    from: Option<DateTimeWithTimeZone>,
+
+    to: Option<DateTimeWithTimeZone>,
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    Guard(claims): Guard,
+    ValidatedQuery(QueryParams {
+        take,
+        before_id,
+        r#type,
+        flag,
+        from,
+        to,
+    }): ValidatedQuery<QueryParams>,
+) -> Result<Json<KeysetPaginationResponse<TransactionWithBalance>>, AppError> {
+    // fetch one more row to know whether there is a next page
+    let mut transactions = transaction_repository::find_transactions(
+        &db,
+        FindTransactionsParams {
+            owner_id: claims.id,
+            r#type,
+            flag,
+            from,
+            to,
+            before_id,
+            take: Some(take + 1),
+        },
+    )
+    .await?;
+
+    let next_cursor = if transactions.len() as u64 > take {
+        transactions.truncate(take as usize);
+        transactions.last().map(|transaction| transaction.id)
+    } else {
+        None
+    };
+
+    Ok(Json(KeysetPaginationResponse {
+        nodes: transactions,
+        next_cursor,
+    }))
+}
@@ -0,0 +1,36 @@
+use crate::{
+    error::{AppError, IntoAppError},
+    extractors::{security::AdminGuard, state::Postgres, validator::ValidatedPayload},
+};
+use database::repositories::promotion_repository;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate)]
+pub struct Payload {
+    #[validate(range(min = 1))]
+    user_id: i32,
+
+    #[validate(range(min = 1))]
+    d_coin: i32,
+
+    /// Shown to the user on the transaction.
+    #[validate(length(min = 1, max = 128))]
+    reason: String,
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    AdminGuard(claims): AdminGuard,
+    ValidatedPayload(payload): ValidatedPayload<Payload>,
+) -> Result<(), AppError> {
+    promotion_repository::grant(
+        &db,
+        payload.user_id,
+        payload.d_coin,
+        claims.id,
+        payload.reason,
+    )
+    .await
+    .map_err(|err| err.into_app_error())
+}
@@ -0,0 +1,28 @@
+use super::shared;
+use crate::{
+    error::{AppError, RejectedApi},
+    extractors::{security::Guard, state::Postgres, validator::ValidatedPayload},
+};
+use database::repositories::match_repository;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate)]
+pub struct Payload {
+    #[validate(length(equal = 32))]
+    invite_code: String,
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    Guard(claims): Guard,
+    ValidatedPayload(payload): ValidatedPayload<Payload>,
+) -> Result<(), AppError> {
+    let r#match = match_repository::find_by_invite_code(&db, &payload.invite_code).await?;
+
+    let Some(r#match) = r#match else {
+        return RejectedApi::ClientError("not found match".to_owned()).into();
+    };
+
+    shared::join_match(&db, r#match, claims.id, Some(&payload.invite_code)).await
+}
@@ -2,57 +2,66 @@ use crate::{
     error::{AppError, IntoAppError, RejectedApi},
     extractors::{
         security::{Claims, SubClaims},
-        state::{Postgres, Redis},
+        state::{Identities, Keys, Postgres, Redis},
     },
     responses::auth::AuthenticateResponse,
 };
 use axum::Json;
+use axum_extra::{headers::UserAgent, TypedHeader};
 use chrono::Duration;
 use database::repositories::user_repository;
 use serde::Deserialize;
-use services::{facebook, google};
 
 use super::shared::generate_tokens;
 
-#[derive(Deserialize)]
-pub enum LoginOption {
-    Facebook,
-    Google,
-}
-
 #[derive(Deserialize)]
 pub struct Payload {
     access_token: String,
-    option: LoginOption,
+    /// Name of the identity provider, e.g. `google`, `facebook` or `apple`.
+    #[serde(alias = "option")]
+    provider: String,
 }
 
 pub async fn handler(
     Postgres(db): Postgres,
     Redis(mut redis_conn): Redis,
+    Keys(key_set): Keys,
+    Identities(providers): Identities,
+    user_agent: Option<TypedHeader<UserAgent>>,
     Json(payload): Json<Payload>,
 ) -> Result<Json<AuthenticateResponse>, AppError> {
-    let (google_id, facebook_id) = match payload.option {
-        LoginOption::Google => google::authorize(&payload.access_token)
-            .await
-            .map(|res| (Some(res.id), None))
-            .map_err(|err| err.into_app_error())?,
-
-        LoginOption::Facebook => facebook::authorize(&payload.access_token)
-            .await
-            .map(|res| (None, Some(res.id)))
-            .map_err(|err| err.into_app_error())?,
-    };
+    let identity = providers
+        .authenticate(&payload.provider, &payload.access_token)
+        .await
+        .map_err(|err| err.into_app_error())?;
 
-    let user = user_repository::find_first_by_platform_id(&db, google_id, facebook_id).await?;
+    let user =
+        user_repository::find_by_identity(&db, &identity.provider, &identity.subject).await?;
 
     let Some(user) = user else {
         return RejectedApi::AuthenticationError("user not found".to_owned()).into();
     };
 
-    let claims = Claims::new(&user, Duration::days(7));
     let sub_claims = SubClaims::new(user.id, Duration::days(365));
+    let claims = Claims::new(&user, &sub_claims.family, Duration::days(7));
 
-    let tokens = generate_tokens(&claims, &sub_claims, &mut redis_conn).await?;
+    let device = user_agent
+        .as_ref()
+        .map(|TypedHeader(user_agent)| user_agent.as_str());
+    let tokens = generate_tokens(&key_set, &claims, &sub_claims, device, &mut redis_conn).await?;
 
     Ok(Json(tokens))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn older_clients_pick_the_provider_as_option() {
+        let payload: Payload =
+            serde_json::from_str(r#"{"access_token":"token","option":"Google"}"#).unwrap();
+
+        assert_eq!(payload.provider, "Google");
+    }
+}
@@ -0,0 +1,11 @@
+use crate::{
+    error::AppError,
+    extractors::{security::Guard, state::Redis},
+    session::revoke_session,
+};
+
+pub async fn handler(Redis(mut redis_conn): Redis, Guard(claims): Guard) -> Result<(), AppError> {
+    revoke_session(claims.id, &claims.sid, &mut redis_conn).await?;
+
+    Ok(())
+}
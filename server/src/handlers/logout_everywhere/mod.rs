@@ -0,0 +1,11 @@
+use crate::{
+    error::AppError,
+    extractors::{security::Guard, state::Redis},
+    session::revoke_all_sessions,
+};
+
+pub async fn handler(Redis(mut redis_conn): Redis, Guard(claims): Guard) -> Result<(), AppError> {
+    revoke_all_sessions(claims.id, &mut redis_conn).await?;
+
+    Ok(())
+}
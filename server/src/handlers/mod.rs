@@ -1,8 +1,32 @@
+pub mod accept_challenge;
+pub mod cancel_match;
+pub mod create_checkout;
+pub mod create_coupon;
 pub mod create_matches;
+pub mod create_tournament;
+pub mod decline_challenge;
 pub mod facebook_register;
+pub mod get_jwks;
+pub mod get_ladder;
+pub mod get_leaderboard;
 pub mod get_matches;
+pub mod get_my_stats;
+pub mod get_seasons;
+pub mod get_sessions;
+pub mod get_statement;
+pub mod get_transactions;
 pub mod google_register;
+pub mod grant_d_coin;
 pub mod join_match;
+pub mod join_match_by_invite_code;
 pub mod login;
+pub mod logout;
+pub mod logout_everywhere;
+pub mod payment_webhook;
+pub mod reconcile_balances;
+pub mod redeem_coupon;
+pub mod refresh_token;
+pub mod register;
+pub mod register_tournament;
 pub mod shared;
 pub mod update_fpl_id;
@@ -0,0 +1,52 @@
+use crate::{
+    error::{AppError, IntoAppError, RejectedApi},
+    extractors::state::{PaymentGateway, Postgres},
+};
+use axum::{body::Bytes, http::HeaderMap};
+use database::repositories::payment_repository;
+use services::payment::PaymentOutcome;
+
+const SIGNATURE_HEADER: &str = "x-payment-signature";
+
+/// Providers retry until they get a success, so a payment already handled is answered with one too.
+pub async fn handler(
+    Postgres(db): Postgres,
+    PaymentGateway(provider): PaymentGateway,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(), AppError> {
+    let Some(signature) = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|signature| signature.to_str().ok())
+    else {
+        return RejectedApi::AuthenticationError("Missing payment signature".into()).into();
+    };
+
+    let event = provider
+        .verify_webhook(signature, &body)
+        .map_err(|err| err.into_app_error())?;
+
+    match event.outcome {
+        PaymentOutcome::Succeeded => {
+            payment_repository::complete_payment(
+                &db,
+                &event.payment_id,
+                provider.name(),
+                &event.provider_reference,
+            )
+            .await
+            .map_err(|err| err.into_app_error())?;
+        }
+        PaymentOutcome::Failed => {
+            payment_repository::fail_payment(
+                &db,
+                &event.payment_id,
+                provider.name(),
+                &event.provider_reference,
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::{
+    error::AppError,
+    extractors::{security::AdminGuard, state::Postgres, validator::ValidatedPayload},
+};
+use axum::Json;
+use database::{models::BalanceDiscrepancy, repositories::transaction_repository};
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate)]
+pub struct Payload {
+    /// Write an adjustment for every discrepancy instead of only reporting them.
+    correct: bool,
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    AdminGuard(claims): AdminGuard,
+    ValidatedPayload(payload): ValidatedPayload<Payload>,
+) -> Result<Json<Vec<BalanceDiscrepancy>>, AppError> {
+    let discrepancies = transaction_repository::find_balance_discrepancies(&db, None).await?;
+
+    if !payload.correct {
+        return Ok(Json(discrepancies));
+    }
+
+    let mut corrected = Vec::with_capacity(discrepancies.len());
+
+    for discrepancy in discrepancies {
+        if let Some(discrepancy) =
+            transaction_repository::correct_balance(&db, discrepancy.user_id, Some(claims.id))
+                .await?
+        {
+            corrected.push(discrepancy);
+        }
+    }
+
+    Ok(Json(corrected))
+}
@@ -0,0 +1,30 @@
+use crate::{
+    error::{AppError, IntoAppError},
+    extractors::{security::Guard, state::Postgres, validator::ValidatedPayload},
+    responses::promotion::RedeemCouponResponse,
+};
+use axum::Json;
+use database::repositories::promotion_repository;
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Validate)]
+pub struct Payload {
+    #[validate(length(min = 1, max = 32))]
+    code: String,
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    Guard(claims): Guard,
+    ValidatedPayload(payload): ValidatedPayload<Payload>,
+) -> Result<Json<RedeemCouponResponse>, AppError> {
+    let coupon = promotion_repository::redeem_coupon(&db, &payload.code.to_uppercase(), claims.id)
+        .await
+        .map_err(|err| err.into_app_error())?;
+
+    Ok(Json(RedeemCouponResponse {
+        code: coupon.code,
+        d_coin: coupon.d_coin,
+    }))
+}
@@ -0,0 +1,70 @@
+use super::shared::{encode_tokens, rotate_renew_token, Rotation};
+use crate::{
+    error::{AppError, RejectedApi},
+    extractors::{
+        security::{Claims, SubClaims},
+        state::{Keys, Postgres, Redis},
+    },
+    responses::auth::AuthenticateResponse,
+    session::{revoke_session, save_session},
+};
+use axum::Json;
+use chrono::Duration;
+use database::repositories::user_repository;
+use jsonwebtoken::errors::ErrorKind;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Payload {
+    renew_token: String,
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    Redis(mut redis_conn): Redis,
+    Keys(key_set): Keys,
+    Json(payload): Json<Payload>,
+) -> Result<Json<AuthenticateResponse>, AppError> {
+    let sub_claims = match key_set.decode_renew::<SubClaims>(&payload.renew_token) {
+        Ok(sub_claims) => sub_claims,
+        Err(err) => {
+            let reason = match err.kind() {
+                ErrorKind::ExpiredSignature => "Expired renew token",
+                _ => "Invalid renew token",
+            };
+
+            return RejectedApi::AuthenticationError(reason.into()).into();
+        }
+    };
+
+    let user = user_repository::find_by_id(&db, sub_claims.sub).await?;
+
+    let Some(user) = user else {
+        return RejectedApi::AuthenticationError("user not found".to_owned()).into();
+    };
+
+    let next_sub_claims = sub_claims.rotate(Duration::days(365));
+
+    match rotate_renew_token(&sub_claims, &next_sub_claims, &mut redis_conn).await? {
+        Rotation::Rotated => {}
+        Rotation::Revoked => {
+            return RejectedApi::AuthenticationError("Revoked renew token".into()).into();
+        }
+        Rotation::Reused => {
+            revoke_session(sub_claims.sub, &sub_claims.family, &mut redis_conn).await?;
+
+            return RejectedApi::AuthenticationError(
+                "Reused renew token, the session has been revoked".into(),
+            )
+            .into();
+        }
+    }
+
+    let claims = Claims::new(&user, &next_sub_claims.family, Duration::days(7));
+
+    save_session(&claims, &next_sub_claims, None, &mut redis_conn).await?;
+
+    let tokens = encode_tokens(&key_set, &claims, &next_sub_claims)?;
+
+    Ok(Json(tokens))
+}
@@ -0,0 +1,88 @@
+use super::shared::{generate_tokens, signup_bonus};
+use crate::{
+    error::{AppError, IntoAppError, RejectedApi},
+    extractors::{
+        security::{Claims, SubClaims},
+        state::{Identities, Keys, Postgres, Redis},
+    },
+    responses::auth::AuthenticateResponse,
+};
+use axum::Json;
+use axum_extra::{headers::UserAgent, TypedHeader};
+use chrono::Duration;
+use database::{
+    entities::{user, user_identity},
+    repositories::{promotion_repository, user_repository},
+    sea_orm::Set,
+};
+
+#[derive(serde::Deserialize)]
+pub struct Payload {
+    fpl_id: Option<i32>,
+    /// Name of the identity provider, e.g. `google`, `facebook` or `apple`.
+    provider: String,
+    access_token: String,
+}
+
+impl Payload {
+    pub fn new(provider: &str, fpl_id: Option<i32>, access_token: String) -> Self {
+        Self {
+            fpl_id,
+            provider: provider.to_owned(),
+            access_token,
+        }
+    }
+}
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    Redis(mut redis_conn): Redis,
+    Keys(key_set): Keys,
+    Identities(providers): Identities,
+    user_agent: Option<TypedHeader<UserAgent>>,
+    Json(payload): Json<Payload>,
+) -> Result<Json<AuthenticateResponse>, AppError> {
+    let identity = providers
+        .authenticate(&payload.provider, &payload.access_token)
+        .await
+        .map_err(|err| err.into_app_error())?;
+
+    let existed_user =
+        user_repository::find_by_identity(&db, &identity.provider, &identity.subject).await?;
+
+    if existed_user.is_some() {
+        return RejectedApi::ClientError("User already existed".to_string()).into();
+    }
+
+    let Some(email) = identity.email.clone() else {
+        return RejectedApi::ClientError("the provider did not share a verified email".into())
+            .into();
+    };
+
+    let new_user = promotion_repository::create_user_with_signup_bonus(
+        &db,
+        user::ActiveModel {
+            fpl_id: Set(payload.fpl_id),
+            email: Set(email),
+            ..Default::default()
+        },
+        user_identity::ActiveModel {
+            provider: Set(identity.provider),
+            subject: Set(identity.subject),
+            email: Set(identity.email),
+            ..Default::default()
+        },
+        signup_bonus(),
+    )
+    .await?;
+
+    let sub_claims = SubClaims::new(new_user.id, Duration::days(365));
+    let claims = Claims::new(&new_user, &sub_claims.family, Duration::days(7));
+
+    let device = user_agent
+        .as_ref()
+        .map(|TypedHeader(user_agent)| user_agent.as_str());
+    let tokens = generate_tokens(&key_set, &claims, &sub_claims, device, &mut redis_conn).await?;
+
+    Ok(Json(tokens))
+}
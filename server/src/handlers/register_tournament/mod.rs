@@ -0,0 +1,16 @@
+use crate::{
+    error::{AppError, IntoAppError},
+    extractors::{security::Guard, state::Postgres},
+};
+use axum::extract::Path;
+use database::repositories::tournament_repository;
+
+pub async fn handler(
+    Postgres(db): Postgres,
+    Guard(claims): Guard,
+    Path(tournament_id): Path<i32>,
+) -> Result<(), AppError> {
+    tournament_repository::register(&db, tournament_id, claims.id)
+        .await
+        .map_err(|err| err.into_app_error())
+}
@@ -1,43 +1,92 @@
 use deadpool_redis::redis::cmd;
-use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
-use std::env;
 
 use crate::{
     extractors::{
+        key_set::KeySet,
         security::{Claims, SubClaims},
         state::RedisConnection,
     },
     responses::auth::AuthenticateResponse,
+    session::{renew_key, save_session, ttl},
 };
 
+/// Outcome of presenting a renew token to its family.
+pub enum Rotation {
+    Rotated,
+    /// The family is unknown, it expired or was revoked.
+    Revoked,
+    /// An older token of the family was presented again, the whole family is now revoked.
+    Reused,
+}
+
+/// Swap the latest token of a family for the next one, atomically so a token is only ever rotated once.
+const ROTATE_SCRIPT: &str = r#"
+local current = redis.call('GET', KEYS[1])
+if not current then
+    return 0
+end
+if current ~= ARGV[1] then
+    redis.call('DEL', KEYS[1])
+    return -1
+end
+redis.call('SET', KEYS[1], ARGV[2], 'EX', ARGV[3])
+return 1
+"#;
+
+/// Issue the tokens of a new session on `device`.
 pub async fn generate_tokens(
+    key_set: &KeySet,
     claims: &Claims,
     sub_claims: &SubClaims,
+    device: Option<&str>,
     redis_conn: &mut RedisConnection,
 ) -> anyhow::Result<AuthenticateResponse> {
-    let secret = env::var("JWT_SECRET")?;
-    let refresh_secret = env::var("JWT_REFRESH_SECRET")?;
-
-    let header = Header::new(Algorithm::HS256);
+    cmd("SET")
+        .arg(renew_key(&sub_claims.family))
+        .arg(&sub_claims.jti)
+        .arg("EX")
+        .arg(ttl(sub_claims.exp))
+        .query_async::<_, ()>(redis_conn)
+        .await?;
 
-    let secret_key = EncodingKey::from_secret(secret.as_bytes());
-    let refresh_key = EncodingKey::from_secret(refresh_secret.as_bytes());
+    save_session(claims, sub_claims, device, redis_conn).await?;
 
-    let access_token = encode(&header, claims, &secret_key)?;
-    let renew_token = encode(&header, sub_claims, &refresh_key)?;
+    encode_tokens(key_set, claims, sub_claims)
+}
 
-    cmd("SET")
-        .arg(renew_key(claims.id))
-        .arg(&renew_token)
+/// Rotate `previous` into `next`, tokens are only issued when it returns `Rotation::Rotated`.
+pub async fn rotate_renew_token(
+    previous: &SubClaims,
+    next: &SubClaims,
+    redis_conn: &mut RedisConnection,
+) -> anyhow::Result<Rotation> {
+    let rotated: i32 = cmd("EVAL")
+        .arg(ROTATE_SCRIPT)
+        .arg(1)
+        .arg(renew_key(&previous.family))
+        .arg(&previous.jti)
+        .arg(&next.jti)
+        .arg(ttl(next.exp))
         .query_async(redis_conn)
         .await?;
 
+    Ok(match rotated {
+        1 => Rotation::Rotated,
+        -1 => Rotation::Reused,
+        _ => Rotation::Revoked,
+    })
+}
+
+pub fn encode_tokens(
+    key_set: &KeySet,
+    claims: &Claims,
+    sub_claims: &SubClaims,
+) -> anyhow::Result<AuthenticateResponse> {
+    let access_token = key_set.encode(claims)?;
+    let renew_token = key_set.encode_renew(sub_claims)?;
+
     Ok(AuthenticateResponse {
         access_token,
         renew_token,
     })
 }
-
-fn renew_key(id: i32) -> String {
-    format!("renew_token_of_{id}")
-}
@@ -0,0 +1,43 @@
+use crate::error::{AppError, IntoAppError, RejectedApi};
+use database::{
+    entities::r#match,
+    repositories::{match_repository, user_repository},
+    sea_orm::DatabaseConnection,
+};
+use services::fantasy::entry;
+
+pub async fn join_match(
+    db: &DatabaseConnection,
+    r#match: r#match::Model,
+    user_id: i32,
+    invite_code: Option<&str>,
+) -> Result<(), AppError> {
+    let user = user_repository::find_by_id(db, user_id).await?;
+
+    let Some(user) = user else {
+        return RejectedApi::AuthenticationError("not found user".to_owned()).into();
+    };
+
+    if let Some(min_week_started) = r#match.min_week_started {
+        let Some(fpl_id) = user.fpl_id else {
+            return RejectedApi::ClientError("user does not have fpl_id".to_owned()).into();
+        };
+
+        let entry = entry::get_entry(fpl_id)
+            .await
+            .map_err(|err| err.into_app_error())?;
+
+        if r#match.gameweek - entry.started_event < min_week_started {
+            return RejectedApi::ClientError(format!(
+                "the fpl team must have started at least {} gameweeks before gameweek {}",
+                min_week_started, r#match.gameweek
+            ))
+            .into();
+        }
+    }
+
+    // The rest of checks run again under lock when joining
+    match_repository::join_match(db, r#match.id, user.id, invite_code)
+        .await
+        .map_err(|err| err.into_app_error())
+}
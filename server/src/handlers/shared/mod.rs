@@ -1,2 +1,6 @@
 mod generate_tokens;
-pub use generate_tokens::generate_tokens;
+mod join_match;
+mod signup_bonus;
+pub use generate_tokens::{encode_tokens, generate_tokens, rotate_renew_token, Rotation};
+pub use join_match::join_match;
+pub use signup_bonus::signup_bonus;
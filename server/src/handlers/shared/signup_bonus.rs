@@ -0,0 +1,7 @@
+/// d_coin credited to every new user, configured with `SIGNUP_BONUS`, none when unset.
+pub fn signup_bonus() -> i32 {
+    std::env::var("SIGNUP_BONUS")
+        .ok()
+        .and_then(|bonus| bonus.trim().parse().ok())
+        .unwrap_or_default()
+}
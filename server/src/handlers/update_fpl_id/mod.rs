@@ -23,7 +23,7 @@ pub async fn handler(
         return RejectedApi::ClientError("user already have fpl_id".to_owned()).into();
     }
 
-    let _ = user_repository::update_fpl_id(&db, claims.id, payload.fpl_id).await?;
+    user_repository::update_fpl_id(&db, claims.id, payload.fpl_id).await?;
 
     Ok(())
 }
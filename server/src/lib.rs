@@ -2,16 +2,21 @@ mod error;
 mod extractors;
 mod handlers;
 mod responses;
+mod session;
 
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 use dotenv::dotenv;
 use extractors::state::AppState;
 use handlers::{
-    create_matches, facebook_register, get_matches, google_register, join_match, login,
-    update_fpl_id,
+    accept_challenge, cancel_match, create_checkout, create_coupon, create_matches,
+    create_tournament, decline_challenge, facebook_register, get_jwks, get_ladder, get_leaderboard,
+    get_matches, get_my_stats, get_seasons, get_sessions, get_statement, get_transactions,
+    google_register, grant_d_coin, join_match, join_match_by_invite_code, login, logout,
+    logout_everywhere, payment_webhook, reconcile_balances, redeem_coupon, refresh_token, register,
+    register_tournament, update_fpl_id,
 };
 use tracing_subscriber::filter::LevelFilter;
 
@@ -27,13 +32,49 @@ pub async fn start() {
     let db_url = std::env::var("DATABASE_URL").expect("db_url must be set");
 
     let app = Router::new()
+        .route("/.well-known/jwks.json", get(get_jwks::handler))
+        .route("/auth/register", post(register::handler))
         .route("/auth/google-register", post(google_register::handler))
         .route("/auth/facebook-register", post(facebook_register::handler))
         .route("/auth/login", post(login::handler))
+        .route("/auth/refresh", post(refresh_token::handler))
+        .route("/auth/sessions", get(get_sessions::handler))
+        .route("/auth/logout", post(logout::handler))
+        .route("/auth/logout-everywhere", post(logout_everywhere::handler))
         .route("/users/update-fpl-id", post(update_fpl_id::handler))
+        .route("/users/me/stats", get(get_my_stats::handler))
+        .route("/users/payments/checkout", post(create_checkout::handler))
+        .route("/users/coupons/redemption", post(redeem_coupon::handler))
+        .route("/admin/coupons", post(create_coupon::handler))
+        .route("/admin/grants", post(grant_d_coin::handler))
+        .route("/payments/webhook", post(payment_webhook::handler))
+        .route("/users/transactions", get(get_transactions::handler))
+        .route("/users/transactions/statement", get(get_statement::handler))
+        .route("/seasons", get(get_seasons::handler))
+        .route("/admin/reconciliation", post(reconcile_balances::handler))
+        .route("/ratings/ladder", get(get_ladder::handler))
+        .route("/leaderboards", get(get_leaderboard::handler))
+        .route("/tournaments", post(create_tournament::handler))
+        .route(
+            "/tournaments/:id/registration",
+            post(register_tournament::handler),
+        )
         .route("/users/matches", post(create_matches::handler))
         .route("/users/matches", get(get_matches::handler))
-        .route("/users/matches/joining", post(join_match::handler))
+        .route("/users/matches/:id", delete(cancel_match::handler))
+        .route("/users/matches/:id/joining", post(join_match::handler))
+        .route(
+            "/users/matches/joining-by-invite-code",
+            post(join_match_by_invite_code::handler),
+        )
+        .route(
+            "/users/matches/:id/challenge/accept",
+            post(accept_challenge::handler),
+        )
+        .route(
+            "/users/matches/:id/challenge/decline",
+            post(decline_challenge::handler),
+        )
         .with_state(AppState::new(&db_url).await.unwrap());
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
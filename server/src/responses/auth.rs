@@ -3,3 +3,14 @@ pub struct AuthenticateResponse {
     pub access_token: String,
     pub renew_token: String,
 }
+
+#[derive(serde::Serialize)]
+pub struct SessionResponse {
+    pub id: String,
+    pub device: String,
+    pub created_at: i64,
+    pub last_used_at: i64,
+
+    /// Whether the request was made from this session.
+    pub current: bool,
+}
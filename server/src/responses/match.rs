@@ -0,0 +1,4 @@
+#[derive(serde::Serialize)]
+pub struct CreateMatchesResponse {
+    pub invite_codes: Vec<String>,
+}
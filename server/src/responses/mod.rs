@@ -1,6 +1,10 @@
 use serde::Serialize;
 
 pub mod auth;
+pub mod r#match;
+pub mod payment;
+pub mod promotion;
+pub mod transaction;
 
 #[derive(Serialize)]
 pub struct PaginationResponse<T> {
@@ -8,3 +12,12 @@ pub struct PaginationResponse<T> {
     pub page: u64,
     pub total: u64,
 }
+
+#[derive(Serialize)]
+pub struct KeysetPaginationResponse<T> {
+    pub nodes: Vec<T>,
+
+    /// Pass it back as `before_id` to fetch the next page, absent on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<i32>,
+}
@@ -0,0 +1,7 @@
+#[derive(serde::Serialize)]
+pub struct CheckoutResponse {
+    pub payment_id: String,
+    pub checkout_url: String,
+    /// In cents.
+    pub amount: i64,
+}
@@ -0,0 +1,6 @@
+/// What a redemption credited, the coupon's other settings stay with the admins.
+#[derive(serde::Serialize)]
+pub struct RedeemCouponResponse {
+    pub code: String,
+    pub d_coin: i32,
+}
@@ -0,0 +1,32 @@
+use database::models::TransactionWithBalance;
+
+#[derive(serde::Serialize)]
+pub struct StatementResponse {
+    pub month: String,
+    pub opening_balance: i64,
+    pub closing_balance: i64,
+    pub total_credit: i64,
+    pub total_debit: i64,
+    pub transactions: Vec<TransactionWithBalance>,
+}
+
+impl StatementResponse {
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("id,created_date,type,flag,d_coin,balance,message\n");
+
+        for transaction in &self.transactions {
+            csv.push_str(&format!(
+                "{},{},{:?},{:?},{},{},\"{}\"\n",
+                transaction.id,
+                transaction.created_date.to_rfc3339(),
+                transaction.r#type,
+                transaction.flag,
+                transaction.d_coin,
+                transaction.balance,
+                transaction.message.replace('"', "\"\""),
+            ));
+        }
+
+        csv
+    }
+}
@@ -0,0 +1,226 @@
+use crate::extractors::{
+    security::{Claims, SubClaims},
+    state::RedisConnection,
+};
+use chrono::Utc;
+use deadpool_redis::redis::cmd;
+use std::collections::HashMap;
+
+/// A signed in device, its id is the family of its renew tokens.
+pub struct Session {
+    pub id: String,
+    pub device: String,
+    pub created_at: i64,
+    pub last_used_at: i64,
+}
+
+/// Record the session of a sign in, or of a refresh when its access token changes.
+///
+/// The access token it replaces is revoked straight away.
+pub async fn save_session(
+    claims: &Claims,
+    sub_claims: &SubClaims,
+    device: Option<&str>,
+    redis_conn: &mut RedisConnection,
+) -> anyhow::Result<()> {
+    let key = session_key(&sub_claims.family);
+    let now = Utc::now().timestamp();
+
+    let previous: HashMap<String, String> =
+        cmd("HGETALL").arg(&key).query_async(redis_conn).await?;
+
+    if let Some(previous_jti) = previous.get("access_jti") {
+        let previous_exp = previous
+            .get("access_exp")
+            .and_then(|exp| exp.parse().ok())
+            .unwrap_or(claims.exp);
+
+        revoke_access_token(previous_jti, previous_exp, redis_conn).await?;
+    }
+
+    let created_at = previous
+        .get("created_at")
+        .cloned()
+        .unwrap_or(now.to_string());
+    let device = device
+        .map(str::to_owned)
+        .or_else(|| previous.get("device").cloned())
+        .unwrap_or("unknown".to_owned());
+
+    cmd("HSET")
+        .arg(&key)
+        .arg("user_id")
+        .arg(claims.id)
+        .arg("device")
+        .arg(device)
+        .arg("created_at")
+        .arg(created_at)
+        .arg("last_used_at")
+        .arg(now)
+        .arg("access_jti")
+        .arg(&claims.jti)
+        .arg("access_exp")
+        .arg(claims.exp)
+        .query_async::<_, ()>(redis_conn)
+        .await?;
+
+    cmd("EXPIRE")
+        .arg(&key)
+        .arg(ttl(sub_claims.exp))
+        .query_async::<_, ()>(redis_conn)
+        .await?;
+
+    cmd("SADD")
+        .arg(sessions_key(claims.id))
+        .arg(&sub_claims.family)
+        .query_async::<_, ()>(redis_conn)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn find_sessions(
+    user_id: i32,
+    redis_conn: &mut RedisConnection,
+) -> anyhow::Result<Vec<Session>> {
+    let session_ids: Vec<String> = cmd("SMEMBERS")
+        .arg(sessions_key(user_id))
+        .query_async(redis_conn)
+        .await?;
+
+    let mut sessions = Vec::with_capacity(session_ids.len());
+
+    for session_id in session_ids {
+        let session: HashMap<String, String> = cmd("HGETALL")
+            .arg(session_key(&session_id))
+            .query_async(redis_conn)
+            .await?;
+
+        // the session expired on its own, forget it
+        if session.is_empty() {
+            cmd("SREM")
+                .arg(sessions_key(user_id))
+                .arg(&session_id)
+                .query_async::<_, ()>(redis_conn)
+                .await?;
+            continue;
+        }
+
+        let timestamp = |field: &str| {
+            session
+                .get(field)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default()
+        };
+
+        sessions.push(Session {
+            created_at: timestamp("created_at"),
+            last_used_at: timestamp("last_used_at"),
+            device: session.get("device").cloned().unwrap_or_default(),
+            id: session_id,
+        });
+    }
+
+    sessions.sort_by_key(|session| -session.last_used_at);
+
+    Ok(sessions)
+}
+
+/// Sign a device out, its renew tokens stop working and its access token is revoked.
+pub async fn revoke_session(
+    user_id: i32,
+    session_id: &str,
+    redis_conn: &mut RedisConnection,
+) -> anyhow::Result<()> {
+    let key = session_key(session_id);
+
+    let (access_jti, access_exp): (Option<String>, Option<u32>) = cmd("HMGET")
+        .arg(&key)
+        .arg("access_jti")
+        .arg("access_exp")
+        .query_async(redis_conn)
+        .await?;
+
+    if let (Some(access_jti), Some(access_exp)) = (access_jti, access_exp) {
+        revoke_access_token(&access_jti, access_exp, redis_conn).await?;
+    }
+
+    cmd("DEL")
+        .arg(&key)
+        .arg(renew_key(session_id))
+        .query_async::<_, ()>(redis_conn)
+        .await?;
+
+    cmd("SREM")
+        .arg(sessions_key(user_id))
+        .arg(session_id)
+        .query_async::<_, ()>(redis_conn)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn revoke_all_sessions(
+    user_id: i32,
+    redis_conn: &mut RedisConnection,
+) -> anyhow::Result<()> {
+    let session_ids: Vec<String> = cmd("SMEMBERS")
+        .arg(sessions_key(user_id))
+        .query_async(redis_conn)
+        .await?;
+
+    for session_id in session_ids {
+        revoke_session(user_id, &session_id, redis_conn).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn is_access_token_revoked(
+    jti: &str,
+    redis_conn: &mut RedisConnection,
+) -> anyhow::Result<bool> {
+    let revoked: bool = cmd("EXISTS")
+        .arg(revoked_key(jti))
+        .query_async(redis_conn)
+        .await?;
+
+    Ok(revoked)
+}
+
+/// Remembered until the token expires on its own.
+async fn revoke_access_token(
+    jti: &str,
+    exp: u32,
+    redis_conn: &mut RedisConnection,
+) -> anyhow::Result<()> {
+    cmd("SET")
+        .arg(revoked_key(jti))
+        .arg(1)
+        .arg("EX")
+        .arg(ttl(exp))
+        .query_async::<_, ()>(redis_conn)
+        .await?;
+
+    Ok(())
+}
+
+pub(crate) fn renew_key(family: &str) -> String {
+    format!("renew_token_family_{family}")
+}
+
+pub(crate) fn ttl(exp: u32) -> i64 {
+    (i64::from(exp) - Utc::now().timestamp()).max(1)
+}
+
+fn session_key(session_id: &str) -> String {
+    format!("session_{session_id}")
+}
+
+fn sessions_key(user_id: i32) -> String {
+    format!("sessions_of_{user_id}")
+}
+
+fn revoked_key(jti: &str) -> String {
+    format!("revoked_access_token_{jti}")
+}
@@ -6,12 +6,43 @@ pub struct FacebookAuthorizeResponse {
     pub id: String,
 }
 
+#[derive(serde::Deserialize, Debug)]
+pub struct FacebookDebugToken {
+    /// The app the token was issued to, missing when the token is not valid.
+    pub app_id: Option<String>,
+    pub is_valid: bool,
+    pub user_id: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct DebugTokenResponse {
+    data: FacebookDebugToken,
+}
+
 pub async fn authorize(access_token: &str) -> Result<FacebookAuthorizeResponse, surf::Error> {
-    let mut response = surf::get(format!(
-        "https://graph.facebook.com/v12.0/me?fields=id,email&access_token=${}",
-        access_token
-    ))
-    .await?;
+    let mut response = surf::get("https://graph.facebook.com/v12.0/me")
+        .query(&[("fields", "id,email"), ("access_token", access_token)])?
+        .await?;
 
     handle_surf_response(&mut response).await
 }
+
+/// Inspect `access_token` with our app access token, `{app_id}|{app_secret}`.
+pub async fn debug_token(
+    access_token: &str,
+    app_id: &str,
+    app_secret: &str,
+) -> Result<FacebookDebugToken, surf::Error> {
+    let app_access_token = format!("{}|{}", app_id, app_secret);
+
+    let mut response = surf::get("https://graph.facebook.com/v12.0/debug_token")
+        .query(&[
+            ("input_token", access_token),
+            ("access_token", app_access_token.as_str()),
+        ])?
+        .await?;
+
+    handle_surf_response::<DebugTokenResponse>(&mut response)
+        .await
+        .map(|response| response.data)
+}
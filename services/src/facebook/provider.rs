@@ -0,0 +1,99 @@
+use super::{authorize, debug_token, FacebookDebugToken};
+use crate::identity::{Identity, IdentityError, IdentityProvider};
+
+/// Signs in with an access token of Facebook Login issued to our app.
+pub struct FacebookProvider {
+    app_id: String,
+    app_secret: String,
+}
+
+impl FacebookProvider {
+    pub fn new(app_id: &str, app_secret: &str) -> Self {
+        Self {
+            app_id: app_id.to_owned(),
+            app_secret: app_secret.to_owned(),
+        }
+    }
+
+    /// Facebook leaves out the app of a token it no longer considers valid.
+    fn check(&self, debug_token: &FacebookDebugToken) -> Result<(), IdentityError> {
+        if !debug_token.is_valid {
+            return Err(IdentityError::InvalidToken("the token is not valid".into()));
+        }
+
+        if debug_token.app_id.as_deref() != Some(self.app_id.as_str()) {
+            return Err(IdentityError::AudienceMismatch(self.name().to_owned()));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl IdentityProvider for FacebookProvider {
+    fn name(&self) -> &str {
+        "facebook"
+    }
+
+    async fn authenticate(&self, token: &str) -> Result<Identity, IdentityError> {
+        let debug_token = debug_token(token, &self.app_id, &self.app_secret).await?;
+        self.check(&debug_token)?;
+
+        let response = authorize(token).await?;
+
+        if debug_token
+            .user_id
+            .is_some_and(|user_id| user_id != response.id)
+        {
+            return Err(IdentityError::InvalidToken(
+                "the token belongs to another user".into(),
+            ));
+        }
+
+        Ok(Identity {
+            provider: self.name().to_owned(),
+            subject: response.id,
+            email: Some(response.email),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn debug_token(json: &str) -> FacebookDebugToken {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn token_of_our_app_is_accepted() {
+        let provider = FacebookProvider::new("app", "secret");
+
+        assert!(provider
+            .check(&debug_token(
+                r#"{"app_id":"app","is_valid":true,"user_id":"1"}"#
+            ))
+            .is_ok());
+    }
+
+    #[test]
+    fn invalid_token_without_an_app_is_rejected_as_invalid() {
+        let provider = FacebookProvider::new("app", "secret");
+
+        assert!(matches!(
+            provider.check(&debug_token(r#"{"is_valid":false}"#)),
+            Err(IdentityError::InvalidToken(_))
+        ));
+    }
+
+    #[test]
+    fn token_of_another_app_is_rejected() {
+        let provider = FacebookProvider::new("app", "secret");
+
+        assert!(matches!(
+            provider.check(&debug_token(r#"{"app_id":"other","is_valid":true}"#)),
+            Err(IdentityError::AudienceMismatch(_))
+        ));
+    }
+}
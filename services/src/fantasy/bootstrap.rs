@@ -30,6 +30,19 @@ pub struct Bootstrap {
     pub events: Vec<Event>,
 }
 
+impl Bootstrap {
+    /// Season named by the years it spans, e.g. "2023-2024", the first deadline is in its starting year.
+    pub fn season(&self) -> Option<(String, &Event)> {
+        let first_event = self.events.iter().min_by_key(|event| event.id)?;
+        let started_year: i32 = first_event.deadline_time.get(0..4)?.parse().ok()?;
+
+        Some((
+            format!("{}-{}", started_year, started_year + 1),
+            first_event,
+        ))
+    }
+}
+
 pub async fn get_bootstrap() -> Result<Bootstrap, surf::Error> {
     let mut response = surf::get("https://fantasy.premierleague.com/api/bootstrap-static/").await?;
 
@@ -0,0 +1,103 @@
+use super::picks::EntryHistory;
+use crate::handle_surf_response;
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ChipPlay {
+    pub name: String,
+    pub time: String,
+    pub event: i32,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct History {
+    pub current: Vec<EntryHistory>,
+    pub chips: Vec<ChipPlay>,
+}
+
+impl History {
+    pub fn event(&self, gameweek: i32) -> Option<&EntryHistory> {
+        self.current
+            .iter()
+            .find(|history| history.event == gameweek)
+    }
+
+    pub fn chip(&self, gameweek: i32) -> Option<&str> {
+        self.chips
+            .iter()
+            .find(|chip| chip.event == gameweek)
+            .map(|chip| chip.name.as_str())
+    }
+
+    /// Points after transfer hits summed over the gameweeks from `from` to `to`.
+    pub fn net_points_between(&self, from: i32, to: i32) -> i32 {
+        self.current
+            .iter()
+            .filter(|history| (from..=to).contains(&history.event))
+            .map(EntryHistory::net_points)
+            .sum()
+    }
+}
+
+pub async fn get_history(fpl_id: i32) -> Result<History, surf::Error> {
+    let mut response = surf::get(format!(
+        "https://fantasy.premierleague.com/api/entry/{}/history/",
+        fpl_id
+    ))
+    .await?;
+
+    handle_surf_response(&mut response).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(gameweeks: &[(i32, i32, i32)]) -> History {
+        History {
+            current: gameweeks
+                .iter()
+                .map(|&(event, points, event_transfers_cost)| EntryHistory {
+                    event,
+                    points,
+                    total_points: points,
+                    rank: None,
+                    overall_rank: None,
+                    bank: 0,
+                    value: 1000,
+                    event_transfers: event_transfers_cost / 4,
+                    event_transfers_cost,
+                    points_on_bench: 0,
+                })
+                .collect(),
+            chips: vec![ChipPlay {
+                name: "bboost".to_owned(),
+                time: String::new(),
+                event: 11,
+            }],
+        }
+    }
+
+    #[test]
+    fn net_points_sum_the_range_after_hits() {
+        let history = history(&[(9, 100, 0), (10, 60, 4), (11, 70, 0), (12, 80, 8)]);
+
+        assert_eq!(history.net_points_between(10, 12), 56 + 70 + 72);
+        assert_eq!(history.net_points_between(11, 11), 70);
+    }
+
+    #[test]
+    fn missing_gameweeks_score_nothing() {
+        let history = history(&[(10, 60, 0)]);
+
+        assert_eq!(history.net_points_between(10, 12), 60);
+        assert_eq!(history.net_points_between(20, 21), 0);
+    }
+
+    #[test]
+    fn chip_is_found_by_gameweek() {
+        let history = history(&[]);
+
+        assert_eq!(history.chip(11), Some("bboost"));
+        assert_eq!(history.chip(12), None);
+    }
+}
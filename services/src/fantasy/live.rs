@@ -0,0 +1,39 @@
+use crate::handle_surf_response;
+use std::collections::HashMap;
+
+#[derive(serde::Deserialize, Debug)]
+pub struct LiveStats {
+    pub minutes: i32,
+    pub total_points: i32,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct LiveElement {
+    pub id: i32,
+    pub stats: LiveStats,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct LiveEvent {
+    pub elements: Vec<LiveElement>,
+}
+
+impl LiveEvent {
+    /// Points of every element keyed by element id.
+    pub fn points_by_element(&self) -> HashMap<i32, i32> {
+        self.elements
+            .iter()
+            .map(|element| (element.id, element.stats.total_points))
+            .collect()
+    }
+}
+
+pub async fn get_live_event(gameweek: i32) -> Result<LiveEvent, surf::Error> {
+    let mut response = surf::get(format!(
+        "https://fantasy.premierleague.com/api/event/{}/live/",
+        gameweek
+    ))
+    .await?;
+
+    handle_surf_response(&mut response).await
+}
@@ -1,2 +1,5 @@
 pub mod bootstrap;
 pub mod entry;
+pub mod history;
+pub mod live;
+pub mod picks;
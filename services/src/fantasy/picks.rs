@@ -0,0 +1,126 @@
+use crate::handle_surf_response;
+use std::collections::HashMap;
+
+#[derive(serde::Deserialize, Debug)]
+pub struct EntryHistory {
+    pub event: i32,
+    pub points: i32,
+    pub total_points: i32,
+    pub rank: Option<i32>,
+    pub overall_rank: Option<i32>,
+    pub bank: i32,
+    pub value: i32,
+    pub event_transfers: i32,
+    pub event_transfers_cost: i32,
+    pub points_on_bench: i32,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct Pick {
+    pub element: i32,
+    pub position: i32,
+    pub multiplier: i32,
+    pub is_captain: bool,
+    pub is_vice_captain: bool,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct EntryPicks {
+    pub active_chip: Option<String>,
+    pub entry_history: EntryHistory,
+    pub picks: Vec<Pick>,
+}
+
+impl EntryHistory {
+    /// Gameweek points after deducting transfer hits.
+    pub fn net_points(&self) -> i32 {
+        self.points - self.event_transfers_cost
+    }
+}
+
+impl EntryPicks {
+    /// Points while the gameweek is running, `multiplier` already covers captaincy,
+    /// triple captain and bench boost.
+    pub fn provisional_points(&self, points_by_element: &HashMap<i32, i32>) -> i32 {
+        let points: i32 = self
+            .picks
+            .iter()
+            .map(|pick| points_by_element.get(&pick.element).unwrap_or(&0) * pick.multiplier)
+            .sum();
+
+        points - self.entry_history.event_transfers_cost
+    }
+}
+
+pub async fn get_entry_picks(fpl_id: i32, gameweek: i32) -> Result<EntryPicks, surf::Error> {
+    let mut response = surf::get(format!(
+        "https://fantasy.premierleague.com/api/entry/{}/event/{}/picks/",
+        fpl_id, gameweek
+    ))
+    .await?;
+
+    handle_surf_response(&mut response).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry_history(event: i32, points: i32, event_transfers_cost: i32) -> EntryHistory {
+        EntryHistory {
+            event,
+            points,
+            total_points: points,
+            rank: None,
+            overall_rank: None,
+            bank: 0,
+            value: 1000,
+            event_transfers: event_transfers_cost / 4,
+            event_transfers_cost,
+            points_on_bench: 0,
+        }
+    }
+
+    fn pick(element: i32, multiplier: i32) -> Pick {
+        Pick {
+            element,
+            position: element,
+            multiplier,
+            is_captain: multiplier > 1,
+            is_vice_captain: false,
+        }
+    }
+
+    #[test]
+    fn provisional_points_apply_multipliers_and_hits() {
+        let entry_picks = EntryPicks {
+            active_chip: None,
+            entry_history: entry_history(10, 0, 4),
+            // captain, a starter and a benched player
+            picks: vec![pick(1, 2), pick(2, 1), pick(3, 0)],
+        };
+        let points_by_element = HashMap::from([(1, 10), (2, 6), (3, 12)]);
+
+        assert_eq!(
+            entry_picks.provisional_points(&points_by_element),
+            10 * 2 + 6 - 4
+        );
+    }
+
+    #[test]
+    fn provisional_points_skip_players_without_live_points() {
+        let entry_picks = EntryPicks {
+            active_chip: Some("3xc".to_owned()),
+            entry_history: entry_history(10, 0, 0),
+            picks: vec![pick(1, 3), pick(2, 1)],
+        };
+        let points_by_element = HashMap::from([(2, 5)]);
+
+        assert_eq!(entry_picks.provisional_points(&points_by_element), 5);
+    }
+
+    #[test]
+    fn net_points_deduct_transfer_hits() {
+        assert_eq!(entry_history(10, 70, 8).net_points(), 62);
+    }
+}
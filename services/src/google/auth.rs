@@ -4,6 +4,18 @@ use crate::handle_surf_response;
 pub struct GoogleAuthorizeResponse {
     pub email: String,
     pub id: String,
+    /// Named `email_verified` by the OpenID Connect userinfo endpoint.
+    #[serde(default, alias = "email_verified")]
+    pub verified_email: bool,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct GoogleTokenInfo {
+    /// The client id the token was issued to.
+    pub aud: String,
+    /// The client that requested the token, when it differs from `aud`.
+    pub azp: Option<String>,
+    pub sub: String,
 }
 
 pub async fn authorize(access_token: &str) -> Result<GoogleAuthorizeResponse, surf::Error> {
@@ -13,3 +25,30 @@ pub async fn authorize(access_token: &str) -> Result<GoogleAuthorizeResponse, su
 
     handle_surf_response(&mut response).await
 }
+
+pub async fn token_info(access_token: &str) -> Result<GoogleTokenInfo, surf::Error> {
+    let mut response = surf::get("https://oauth2.googleapis.com/tokeninfo")
+        .query(&[("access_token", access_token)])?
+        .await?;
+
+    handle_surf_response(&mut response).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn email_verification_is_read_from_either_userinfo_version() {
+        let v1: GoogleAuthorizeResponse =
+            serde_json::from_str(r#"{"id":"1","email":"a@b.c","verified_email":true}"#).unwrap();
+        let oidc: GoogleAuthorizeResponse =
+            serde_json::from_str(r#"{"id":"1","email":"a@b.c","email_verified":true}"#).unwrap();
+        let missing: GoogleAuthorizeResponse =
+            serde_json::from_str(r#"{"id":"1","email":"a@b.c"}"#).unwrap();
+
+        assert!(v1.verified_email);
+        assert!(oidc.verified_email);
+        assert!(!missing.verified_email);
+    }
+}
@@ -1,3 +1,5 @@
 mod auth;
+mod provider;
 
 pub use auth::*;
+pub use provider::*;
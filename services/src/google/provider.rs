@@ -0,0 +1,87 @@
+use super::{authorize, token_info, GoogleTokenInfo};
+use crate::identity::{Identity, IdentityError, IdentityProvider};
+
+/// Signs in with an access token of Google OAuth issued to one of our clients.
+pub struct GoogleProvider {
+    client_ids: Vec<String>,
+}
+
+impl GoogleProvider {
+    /// `client_ids` is comma-separated, the web, Android and iOS apps each have their own.
+    pub fn new(client_ids: &str) -> Self {
+        Self {
+            client_ids: client_ids
+                .split(',')
+                .map(str::trim)
+                .filter(|client_id| !client_id.is_empty())
+                .map(str::to_owned)
+                .collect(),
+        }
+    }
+
+    fn is_ours(&self, token_info: &GoogleTokenInfo) -> bool {
+        std::iter::once(&token_info.aud)
+            .chain(&token_info.azp)
+            .any(|client_id| self.client_ids.contains(client_id))
+    }
+}
+
+#[async_trait::async_trait]
+impl IdentityProvider for GoogleProvider {
+    fn name(&self) -> &str {
+        "google"
+    }
+
+    async fn authenticate(&self, token: &str) -> Result<Identity, IdentityError> {
+        let token_info = token_info(token).await?;
+
+        if !self.is_ours(&token_info) {
+            return Err(IdentityError::AudienceMismatch(self.name().to_owned()));
+        }
+
+        let response = authorize(token).await?;
+
+        if response.id != token_info.sub {
+            return Err(IdentityError::InvalidToken(
+                "the token belongs to another user".into(),
+            ));
+        }
+
+        // an unverified address could belong to someone else, it is not taken as the user's
+        Ok(Identity {
+            provider: self.name().to_owned(),
+            subject: response.id,
+            email: Some(response.email).filter(|_| response.verified_email),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_info(aud: &str, azp: Option<&str>) -> GoogleTokenInfo {
+        GoogleTokenInfo {
+            aud: aud.to_owned(),
+            azp: azp.map(str::to_owned),
+            sub: "1".to_owned(),
+        }
+    }
+
+    #[test]
+    fn any_configured_client_is_ours() {
+        let provider = GoogleProvider::new("web, android");
+
+        assert!(provider.is_ours(&token_info("web", None)));
+        assert!(provider.is_ours(&token_info("android", None)));
+        assert!(!provider.is_ours(&token_info("other", None)));
+    }
+
+    #[test]
+    fn authorized_party_is_checked_too() {
+        let provider = GoogleProvider::new("android");
+
+        assert!(provider.is_ours(&token_info("web-backend", Some("android"))));
+        assert!(!provider.is_ours(&token_info("web-backend", Some("other"))));
+    }
+}
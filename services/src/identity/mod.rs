@@ -0,0 +1,81 @@
+mod oidc;
+
+pub use oidc::*;
+
+use std::{collections::HashMap, sync::Arc};
+
+/// Who signed in, normalized across providers.
+#[derive(Debug)]
+pub struct Identity {
+    /// Name of the provider that vouched for the identity.
+    pub provider: String,
+    /// The provider's own id of the user, unique within the provider.
+    pub subject: String,
+    /// Some providers only share it on the first sign in.
+    pub email: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum IdentityError {
+    UnknownProvider(String),
+    InvalidToken(String),
+    /// The token is genuine but was issued to another app, it must not sign anyone in here.
+    AudienceMismatch(String),
+    Request(surf::Error),
+}
+
+impl std::fmt::Display for IdentityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IdentityError::UnknownProvider(name) => write!(f, "unknown identity provider {}", name),
+            IdentityError::InvalidToken(reason) => write!(f, "invalid token: {}", reason),
+            IdentityError::AudienceMismatch(provider) => {
+                write!(f, "the {} token was issued to another app", provider)
+            }
+            IdentityError::Request(err) => write!(f, "identity provider error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for IdentityError {}
+
+impl From<surf::Error> for IdentityError {
+    fn from(err: surf::Error) -> Self {
+        IdentityError::Request(err)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait IdentityProvider: Send + Sync {
+    /// Clients pick the provider by this name, identities are stored under it.
+    fn name(&self) -> &str;
+
+    /// `token` is whatever the client got from the provider, an access token or an ID token.
+    async fn authenticate(&self, token: &str) -> Result<Identity, IdentityError>;
+}
+
+/// The providers users can sign in with, keyed by name.
+#[derive(Default)]
+pub struct IdentityProviders {
+    providers: HashMap<String, Arc<dyn IdentityProvider>>,
+}
+
+impl IdentityProviders {
+    pub fn register(&mut self, provider: impl IdentityProvider + 'static) {
+        self.providers
+            .insert(provider.name().to_lowercase(), Arc::new(provider));
+    }
+
+    /// `provider` is matched case-insensitively, older clients send `Google` or `Facebook`.
+    pub async fn authenticate(
+        &self,
+        provider: &str,
+        token: &str,
+    ) -> Result<Identity, IdentityError> {
+        self.providers
+            .get(&provider.to_lowercase())
+            .ok_or_else(|| IdentityError::UnknownProvider(provider.to_owned()))?
+            .authenticate(token)
+            .await
+    }
+}
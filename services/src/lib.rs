@@ -1,6 +1,8 @@
 pub mod facebook;
 pub mod fantasy;
 pub mod google;
+pub mod identity;
+pub mod payment;
 pub use surf::{Error, StatusCode};
 
 async fn handle_surf_response<T: serde::de::DeserializeOwned>(
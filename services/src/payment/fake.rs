@@ -0,0 +1,116 @@
+use super::{CheckoutIntent, PaymentError, PaymentEvent, PaymentOutcome, PaymentProvider};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+/// Local provider settling nothing for real, its webhooks are signed with HMAC-SHA256 over the body.
+pub struct FakePaymentProvider {
+    webhook_secret: String,
+}
+
+#[derive(serde::Deserialize)]
+struct FakeWebhookPayload {
+    payment_id: String,
+    reference: String,
+    outcome: PaymentOutcome,
+}
+
+impl FakePaymentProvider {
+    pub fn new(webhook_secret: impl Into<String>) -> Self {
+        Self {
+            webhook_secret: webhook_secret.into(),
+        }
+    }
+
+    /// Hex signature the webhook of `body` must carry.
+    pub fn sign(&self, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.webhook_secret.as_bytes())
+            .expect("HMAC takes a key of any size");
+        mac.update(body);
+
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+#[async_trait::async_trait]
+impl PaymentProvider for FakePaymentProvider {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    async fn create_checkout(
+        &self,
+        _user_id: i32,
+        _d_coin: i32,
+        _amount: i64,
+    ) -> Result<CheckoutIntent, PaymentError> {
+        let payment_id = Uuid::new_v4().simple().to_string();
+
+        Ok(CheckoutIntent {
+            checkout_url: format!("https://checkout.fake/{}", payment_id),
+            payment_id,
+        })
+    }
+
+    fn verify_webhook(&self, signature: &str, body: &[u8]) -> Result<PaymentEvent, PaymentError> {
+        let signature = hex::decode(signature).map_err(|_| PaymentError::InvalidSignature)?;
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.webhook_secret.as_bytes())
+            .expect("HMAC takes a key of any size");
+        mac.update(body);
+        mac.verify_slice(&signature)
+            .map_err(|_| PaymentError::InvalidSignature)?;
+
+        let payload: FakeWebhookPayload = serde_json::from_slice(body)
+            .map_err(|err| PaymentError::InvalidPayload(err.to_string()))?;
+
+        Ok(PaymentEvent {
+            payment_id: payload.payment_id,
+            provider_reference: payload.reference,
+            outcome: payload.outcome,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &[u8] = br#"{"payment_id":"p1","reference":"ch_1","outcome":"succeeded"}"#;
+
+    #[test]
+    fn signed_webhook_is_verified() {
+        let provider = FakePaymentProvider::new("secret");
+
+        let event = provider.verify_webhook(&provider.sign(BODY), BODY).unwrap();
+
+        assert_eq!(event.payment_id, "p1");
+        assert_eq!(event.provider_reference, "ch_1");
+        assert_eq!(event.outcome, PaymentOutcome::Succeeded);
+    }
+
+    #[test]
+    fn tampered_body_is_rejected() {
+        let provider = FakePaymentProvider::new("secret");
+        let signature = provider.sign(BODY);
+        let tampered = br#"{"payment_id":"p2","reference":"ch_1","outcome":"succeeded"}"#;
+
+        assert!(matches!(
+            provider.verify_webhook(&signature, tampered),
+            Err(PaymentError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn missing_or_foreign_signature_is_rejected() {
+        let provider = FakePaymentProvider::new("secret");
+        let foreign = FakePaymentProvider::new("other").sign(BODY);
+
+        for signature in ["", "not-hex", foreign.as_str()] {
+            assert!(matches!(
+                provider.verify_webhook(signature, BODY),
+                Err(PaymentError::InvalidSignature)
+            ));
+        }
+    }
+}
@@ -0,0 +1,63 @@
+mod fake;
+
+pub use fake::*;
+
+#[derive(Debug)]
+pub struct CheckoutIntent {
+    /// Our id of the payment, the webhook refers to it.
+    pub payment_id: String,
+    pub checkout_url: String,
+}
+
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentOutcome {
+    Succeeded,
+    Failed,
+}
+
+/// A verified webhook call, normalized across providers.
+#[derive(Debug)]
+pub struct PaymentEvent {
+    pub payment_id: String,
+    /// The provider's own id of the charge.
+    pub provider_reference: String,
+    pub outcome: PaymentOutcome,
+}
+
+#[derive(Debug)]
+pub enum PaymentError {
+    InvalidSignature,
+    InvalidPayload(String),
+    Provider(String),
+}
+
+impl std::fmt::Display for PaymentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaymentError::InvalidSignature => write!(f, "invalid webhook signature"),
+            PaymentError::InvalidPayload(reason) => {
+                write!(f, "invalid webhook payload: {}", reason)
+            }
+            PaymentError::Provider(reason) => write!(f, "payment provider error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PaymentError {}
+
+#[async_trait::async_trait]
+pub trait PaymentProvider: Send + Sync {
+    /// Stored with every payment so a webhook is checked against the provider that created it.
+    fn name(&self) -> &'static str;
+
+    /// `amount` is in the smallest currency unit.
+    async fn create_checkout(
+        &self,
+        user_id: i32,
+        d_coin: i32,
+        amount: i64,
+    ) -> Result<CheckoutIntent, PaymentError>;
+
+    fn verify_webhook(&self, signature: &str, body: &[u8]) -> Result<PaymentEvent, PaymentError>;
+}
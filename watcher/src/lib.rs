@@ -18,7 +18,7 @@ where
     F: Fn(P, &'a DatabaseConnection) -> Fut,
 {
     let mut listener = PgListener::connect_with(pool).await.unwrap();
-    let channels: Vec<&str> = workers.keys().into_iter().map(|key| *key).collect();
+    let channels: Vec<&str> = workers.keys().copied().collect();
     listener.listen_all(channels).await?;
 
     loop {